@@ -0,0 +1,31 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use orbipacket::{encode::Encoder, DeviceId, Packet, Payload, Timestamp, TmPacket};
+
+fn packet() -> Packet {
+    Packet::TmPacket(TmPacket::new(
+        DeviceId::Accelerometer,
+        Timestamp::new(123_456).unwrap(),
+        Payload::from_raw_bytes([0xA5; 64]).unwrap(),
+    ))
+}
+
+fn encode(c: &mut Criterion) {
+    let packet = packet();
+    let mut group = c.benchmark_group("encode");
+
+    // Both use a buffer allocated once, so they should perform the same
+    group.bench_function("reused_buffer", |b| {
+        let mut buffer = [0u8; Packet::MAX_ENCODE_BUFFER_SIZE];
+        b.iter(|| black_box(black_box(&packet).encode(&mut buffer).unwrap().len()))
+    });
+
+    group.bench_function("encoder", |b| {
+        let mut encoder = Encoder::<{ Packet::MAX_ENCODE_BUFFER_SIZE }>::new();
+        b.iter(|| black_box(encoder.encode_next(black_box(&packet)).unwrap().len()))
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, encode);
+criterion_main!(benches);
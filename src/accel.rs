@@ -0,0 +1,247 @@
+//! Canonical payload layout for [`DeviceId::Accelerometer`](crate::DeviceId::Accelerometer) packets.
+//!
+//! An acceleration sample is serialized as the following little endian fields, in order:
+//! - 4 bytes for the acceleration along the X axis, in m/s² (`f32`)
+//! - 4 bytes for the acceleration along the Y axis, in m/s² (`f32`)
+//! - 4 bytes for the acceleration along the Z axis, in m/s² (`f32`)
+//!
+//! A batch of samples is serialized as the concatenation of its samples, oldest first.
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::Payload;
+
+/// Error that can occur when reading an [`AccelSample`] or [`AccelBatch`] from a payload
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum AccelError {
+    /// The payload doesn't have the size of the serialized samples
+    #[error("invalid accelerometer payload length (expected {expected}, found {found})")]
+    InvalidLength { expected: usize, found: usize },
+}
+
+/// An acceleration sample reported by the accelerometer
+///
+/// # Example
+/// ```
+/// # use orbipacket::accel::AccelSample;
+/// let sample = AccelSample { x: 0.1, y: -0.2, z: 9.8 };
+///
+/// let payload = sample.to_payload();
+///
+/// assert_eq!(payload.length(), AccelSample::SIZE);
+/// assert_eq!(AccelSample::from_payload(&payload)?, sample);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct AccelSample {
+    /// Acceleration along the X axis, in m/s²
+    pub x: f32,
+    /// Acceleration along the Y axis, in m/s²
+    pub y: f32,
+    /// Acceleration along the Z axis, in m/s²
+    pub z: f32,
+}
+
+impl AccelSample {
+    /// Size of a serialized sample, in bytes
+    pub const SIZE: usize = 3 * 4;
+
+    /// Serialize the sample into a payload.
+    pub fn to_payload(&self) -> Payload {
+        // Unwrapping is safe here because a sample is much smaller than the maximum payload size
+        Payload::from_raw_bytes(self.to_bytes()).unwrap()
+    }
+
+    /// Read a sample from a payload.
+    ///
+    /// # Errors
+    /// If the payload isn't exactly [`AccelSample::SIZE`] bytes long, an error variant is
+    /// returned.
+    pub fn from_payload(payload: &Payload) -> Result<Self, AccelError> {
+        let bytes = payload
+            .as_bytes()
+            .try_into()
+            .map_err(|_| AccelError::InvalidLength {
+                expected: Self::SIZE,
+                found: payload.length(),
+            })?;
+
+        Ok(Self::from_bytes(bytes))
+    }
+
+    fn to_bytes(self) -> [u8; Self::SIZE] {
+        let mut bytes = [0u8; Self::SIZE];
+        bytes[0..4].copy_from_slice(&self.x.to_le_bytes());
+        bytes[4..8].copy_from_slice(&self.y.to_le_bytes());
+        bytes[8..12].copy_from_slice(&self.z.to_le_bytes());
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8; Self::SIZE]) -> Self {
+        // Unwrapping is safe here because the ranges have the size of each field
+        AccelSample {
+            x: f32::from_le_bytes(bytes[0..4].try_into().unwrap()),
+            y: f32::from_le_bytes(bytes[4..8].try_into().unwrap()),
+            z: f32::from_le_bytes(bytes[8..12].try_into().unwrap()),
+        }
+    }
+}
+
+/// A batch of `N` acceleration samples, carried in a single payload for high-rate logging
+///
+/// At most 21 samples fit in a payload. Using a larger `N` results in a compilation error.
+///
+/// # Example
+/// ```
+/// # use orbipacket::accel::{AccelBatch, AccelSample};
+/// let batch = AccelBatch {
+///     samples: [
+///         AccelSample { x: 0.0, y: 0.0, z: 9.8 },
+///         AccelSample { x: 0.1, y: 0.0, z: 9.7 },
+///     ],
+/// };
+///
+/// let payload = batch.to_payload();
+///
+/// assert_eq!(payload.length(), AccelBatch::<2>::SIZE);
+/// assert_eq!(AccelBatch::from_payload(&payload)?, batch);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+///
+/// ```compile_fail
+/// # use orbipacket::accel::{AccelBatch, AccelSample};
+/// let batch = AccelBatch { samples: [AccelSample::default(); 22] };
+/// let payload = batch.to_payload();
+/// ```
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct AccelBatch<const N: usize> {
+    /// The samples, oldest first
+    pub samples: [AccelSample; N],
+}
+
+impl<const N: usize> AccelBatch<N> {
+    /// Size of a serialized batch, in bytes
+    pub const SIZE: usize = N * AccelSample::SIZE;
+
+    /// Evaluated when serializing, to reject batches which don't fit in a payload
+    const FITS_IN_PAYLOAD: () = assert!(
+        Self::SIZE <= Payload::MAX_SIZE,
+        "accelerometer batch too large to fit in a payload"
+    );
+
+    /// Serialize the batch into a payload.
+    pub fn to_payload(&self) -> Payload {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::FITS_IN_PAYLOAD;
+
+        let mut bytes = [0u8; Payload::MAX_SIZE];
+        for (chunk, sample) in bytes.chunks_exact_mut(AccelSample::SIZE).zip(&self.samples) {
+            chunk.copy_from_slice(&sample.to_bytes());
+        }
+
+        // Unwrapping is safe here because the batch was checked to fit in a payload
+        Payload::from_raw_bytes(&bytes[..Self::SIZE]).unwrap()
+    }
+
+    /// Read a batch from a payload.
+    ///
+    /// # Errors
+    /// If the payload isn't exactly [`AccelBatch::SIZE`] bytes long, an error variant is
+    /// returned.
+    pub fn from_payload(payload: &Payload) -> Result<Self, AccelError> {
+        let bytes = payload.as_bytes();
+        if bytes.len() != Self::SIZE {
+            return Err(AccelError::InvalidLength {
+                expected: Self::SIZE,
+                found: bytes.len(),
+            });
+        }
+
+        let mut samples = [AccelSample::default(); N];
+        for (sample, chunk) in samples
+            .iter_mut()
+            .zip(bytes.chunks_exact(AccelSample::SIZE))
+        {
+            // Unwrapping is safe here because chunks have the size of a sample
+            *sample = AccelSample::from_bytes(chunk.try_into().unwrap());
+        }
+        Ok(AccelBatch { samples })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_payload_writes_axes_in_order() {
+        let sample = AccelSample {
+            x: 1.0,
+            y: 2.0,
+            z: 3.0,
+        };
+
+        let payload = sample.to_payload();
+
+        let bytes = payload.as_bytes();
+        assert_eq!(bytes[0..4], 1.0f32.to_le_bytes());
+        assert_eq!(bytes[4..8], 2.0f32.to_le_bytes());
+        assert_eq!(bytes[8..12], 3.0f32.to_le_bytes());
+    }
+
+    #[test]
+    fn batch_to_payload_concatenates_samples() {
+        let first = AccelSample {
+            x: 1.0,
+            y: 2.0,
+            z: 3.0,
+        };
+        let second = AccelSample {
+            x: 4.0,
+            y: 5.0,
+            z: 6.0,
+        };
+        let batch = AccelBatch {
+            samples: [first, second],
+        };
+
+        let payload = batch.to_payload();
+
+        assert_eq!(payload.as_bytes()[..12], *first.to_payload().as_bytes());
+        assert_eq!(payload.as_bytes()[12..], *second.to_payload().as_bytes());
+    }
+
+    #[test]
+    fn largest_batch_fits_in_payload() {
+        let batch = AccelBatch {
+            samples: [AccelSample::default(); 21],
+        };
+
+        assert_eq!(batch.to_payload().length(), 252);
+    }
+
+    #[test]
+    fn from_payload_rejects_invalid_length() {
+        let payload = Payload::from_raw_bytes([0u8; AccelSample::SIZE + 1]).unwrap();
+
+        assert_eq!(
+            AccelSample::from_payload(&payload),
+            Err(AccelError::InvalidLength {
+                expected: AccelSample::SIZE,
+                found: AccelSample::SIZE + 1
+            })
+        );
+        assert_eq!(
+            AccelBatch::<1>::from_payload(&payload),
+            Err(AccelError::InvalidLength {
+                expected: AccelSample::SIZE,
+                found: AccelSample::SIZE + 1
+            })
+        );
+    }
+}
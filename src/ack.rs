@@ -0,0 +1,210 @@
+//! Matching of telecommands with the telemetry acknowledging them.
+//!
+//! This module requires the `alloc` feature, since commands awaiting an acknowledgement are
+//! buffered on the heap.
+
+use alloc::collections::VecDeque;
+use core::time::Duration;
+
+use crate::{Packet, TcPacket, TmPacket};
+
+/// Iterator adapter pairing each telecommand with the telemetry acknowledging it.
+///
+/// A telecommand is acknowledged by the first following telemetry packet from the same device
+/// whose timestamp is at most `window` after the command's. Telemetry which doesn't acknowledge
+/// any pending command is discarded.
+///
+/// Commands are yielded as soon as they're acknowledged, or as soon as a packet with a timestamp
+/// past their window is observed, in which case they are paired with `None`. Commands still
+/// pending when the underlying iterator ends are flushed, also paired with `None`.
+///
+/// Timestamps are expected to be non-decreasing, as packets are usually received in the order
+/// they're produced.
+///
+/// # Example
+/// ```
+/// use core::time::Duration;
+/// use orbipacket::{ack::AckMatcher, DeviceId, Packet, Payload, TcPacket, Timestamp, TmPacket};
+///
+/// let command = TcPacket::new(DeviceId::Gps, Timestamp::new(1_000)?, Payload::from_u8(1));
+/// let ack = TmPacket::new(DeviceId::Gps, Timestamp::new(1_500)?, Payload::from_u8(1));
+/// let packets = [Packet::TcPacket(command), Packet::TmPacket(ack)];
+///
+/// let mut matcher = AckMatcher::new(packets, Duration::from_millis(1));
+///
+/// assert_eq!(matcher.next(), Some((command, Some(ack))));
+/// assert_eq!(matcher.next(), None);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct AckMatcher<I> {
+    packets: I,
+    window: u64,
+    pending: VecDeque<TcPacket>,
+    resolved: VecDeque<(TcPacket, Option<TmPacket>)>,
+}
+
+impl<I: Iterator<Item = Packet>> AckMatcher<I> {
+    /// Create a matcher over the given packets, waiting up to `window` for each acknowledgement.
+    ///
+    /// Durations longer than the range of a [`Timestamp`](crate::Timestamp) are saturated.
+    pub fn new<P: IntoIterator<IntoIter = I>>(packets: P, window: Duration) -> Self {
+        AckMatcher {
+            packets: packets.into_iter(),
+            window: window.as_micros().try_into().unwrap_or(u64::MAX),
+            pending: VecDeque::new(),
+            resolved: VecDeque::new(),
+        }
+    }
+
+    /// Number of commands still awaiting an acknowledgement
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Resolve as unacknowledged all pending commands whose window ended before `now`.
+    fn expire(&mut self, now: u64) {
+        let window = self.window;
+        let mut idx = 0;
+        while idx < self.pending.len() {
+            if now.saturating_sub(self.pending[idx].timestamp().get()) > window {
+                // Unwrapping is safe here because idx is within bounds
+                let command = self.pending.remove(idx).unwrap();
+                self.resolved.push_back((command, None));
+            } else {
+                idx += 1;
+            }
+        }
+    }
+}
+
+impl<I: Iterator<Item = Packet>> Iterator for AckMatcher<I> {
+    type Item = (TcPacket, Option<TmPacket>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(pair) = self.resolved.pop_front() {
+                return Some(pair);
+            }
+
+            match self.packets.next() {
+                Some(Packet::TcPacket(command)) => {
+                    self.expire(command.timestamp().get());
+                    self.pending.push_back(command);
+                }
+                Some(Packet::TmPacket(telemetry)) => {
+                    self.expire(telemetry.timestamp().get());
+                    let acknowledged = self.pending.iter().position(|command| {
+                        command.device_id() == telemetry.device_id()
+                            && command.timestamp() <= telemetry.timestamp()
+                    });
+                    if let Some(idx) = acknowledged {
+                        // Unwrapping is safe here because idx was just found
+                        let command = self.pending.remove(idx).unwrap();
+                        self.resolved.push_back((command, Some(telemetry)));
+                    }
+                }
+                None => return self.pending.pop_front().map(|command| (command, None)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DeviceId, Payload, Timestamp};
+
+    fn tc(device_id: DeviceId, timestamp: u64) -> TcPacket {
+        TcPacket::new(
+            device_id,
+            Timestamp::new(timestamp).unwrap(),
+            Payload::new(),
+        )
+    }
+
+    fn tm(device_id: DeviceId, timestamp: u64) -> TmPacket {
+        TmPacket::new(
+            device_id,
+            Timestamp::new(timestamp).unwrap(),
+            Payload::new(),
+        )
+    }
+
+    #[test]
+    fn matches_by_device_id() {
+        let packets = [
+            Packet::TcPacket(tc(DeviceId::Gps, 0)),
+            Packet::TcPacket(tc(DeviceId::Altimeter, 1)),
+            Packet::TmPacket(tm(DeviceId::Altimeter, 2)),
+            Packet::TmPacket(tm(DeviceId::Gps, 3)),
+        ];
+
+        let pairs: Vec<_> = AckMatcher::new(packets, Duration::from_micros(10)).collect();
+
+        assert_eq!(
+            pairs,
+            [
+                (tc(DeviceId::Altimeter, 1), Some(tm(DeviceId::Altimeter, 2))),
+                (tc(DeviceId::Gps, 0), Some(tm(DeviceId::Gps, 3))),
+            ]
+        );
+    }
+
+    #[test]
+    fn flushes_commands_after_window() {
+        let packets = [
+            Packet::TcPacket(tc(DeviceId::Gps, 0)),
+            Packet::TmPacket(tm(DeviceId::Altimeter, 11)),
+            Packet::TmPacket(tm(DeviceId::Gps, 12)),
+        ];
+        let mut matcher = AckMatcher::new(packets, Duration::from_micros(10));
+
+        assert_eq!(matcher.next(), Some((tc(DeviceId::Gps, 0), None)));
+        assert_eq!(matcher.pending(), 0);
+        assert_eq!(matcher.next(), None);
+    }
+
+    #[test]
+    fn matches_at_end_of_window() {
+        let packets = [
+            Packet::TcPacket(tc(DeviceId::Gps, 0)),
+            Packet::TmPacket(tm(DeviceId::Gps, 10)),
+        ];
+
+        let pairs: Vec<_> = AckMatcher::new(packets, Duration::from_micros(10)).collect();
+
+        assert_eq!(pairs, [(tc(DeviceId::Gps, 0), Some(tm(DeviceId::Gps, 10)))]);
+    }
+
+    #[test]
+    fn matches_oldest_pending_command_first() {
+        let packets = [
+            Packet::TcPacket(tc(DeviceId::Gps, 0)),
+            Packet::TcPacket(tc(DeviceId::Gps, 1)),
+            Packet::TmPacket(tm(DeviceId::Gps, 2)),
+        ];
+
+        let pairs: Vec<_> = AckMatcher::new(packets, Duration::from_micros(10)).collect();
+
+        assert_eq!(
+            pairs,
+            [
+                (tc(DeviceId::Gps, 0), Some(tm(DeviceId::Gps, 2))),
+                (tc(DeviceId::Gps, 1), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn ignores_telemetry_preceding_command() {
+        let packets = [
+            Packet::TcPacket(tc(DeviceId::Gps, 5)),
+            Packet::TmPacket(tm(DeviceId::Gps, 4)),
+        ];
+
+        let pairs: Vec<_> = AckMatcher::new(packets, Duration::from_micros(10)).collect();
+
+        assert_eq!(pairs, [(tc(DeviceId::Gps, 5), None)]);
+    }
+}
@@ -0,0 +1,105 @@
+//! Selection of the CRC-16 algorithm protecting packets.
+//!
+//! The protocol specifies [`OpenSafetyB`], which is used by default. Deployments standardized on a
+//! different algorithm can implement [`Crc16Variant`] and select it through
+//! [`EncodeOptions::crc_variant`](crate::encode::EncodeOptions::crc_variant) and
+//! [`DecodeOptions::crc_variant`](crate::decode::DecodeOptions::crc_variant). Both ends of a link
+//! must agree on the variant, since it isn't recorded in the packets.
+//!
+//! # Example
+//! ```
+//! use orbipacket::checksum::Crc16Variant;
+//!
+//! static CRC_16_XMODEM: crc::Crc<u16> = crc::Crc::<u16>::new(&crc::CRC_16_XMODEM);
+//!
+//! #[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+//! struct Xmodem;
+//!
+//! impl Crc16Variant for Xmodem {
+//!     fn checksum(bytes: &[u8]) -> u16 {
+//!         CRC_16_XMODEM.checksum(bytes)
+//!     }
+//! }
+//! ```
+
+#[cfg(any(test, not(feature = "crc-constant-time")))]
+use crate::encode::CRC;
+
+/// A CRC-16 algorithm used to compute packet checksums
+pub trait Crc16Variant {
+    /// Compute the checksum of the given bytes.
+    fn checksum(bytes: &[u8]) -> u16;
+}
+
+/// The CRC-16/OPENSAFETY-B algorithm, as specified by the protocol
+///
+/// By default, the checksum is computed with a lookup table. Enabling the `crc-constant-time`
+/// feature switches to a slower bitwise implementation, whose timing doesn't depend on the data,
+/// producing the same checksums.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct OpenSafetyB;
+
+impl Crc16Variant for OpenSafetyB {
+    #[cfg(not(feature = "crc-constant-time"))]
+    fn checksum(bytes: &[u8]) -> u16 {
+        CRC.checksum(bytes)
+    }
+
+    #[cfg(feature = "crc-constant-time")]
+    fn checksum(bytes: &[u8]) -> u16 {
+        bitwise_checksum(bytes)
+    }
+}
+
+/// Compute the CRC-16/OPENSAFETY-B checksum one bit at a time, without branches or table lookups
+/// depending on the data.
+#[cfg(any(test, feature = "crc-constant-time"))]
+fn bitwise_checksum(bytes: &[u8]) -> u16 {
+    const POLY: u16 = crc::CRC_16_OPENSAFETY_B.poly;
+
+    let mut crc = crc::CRC_16_OPENSAFETY_B.init;
+    for &byte in bytes {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            // All ones if the top bit is set, all zeros otherwise
+            let mask = 0u16.wrapping_sub(crc >> 15);
+            crc = (crc << 1) ^ (POLY & mask);
+        }
+    }
+    crc ^ crc::CRC_16_OPENSAFETY_B.xorout
+}
+
+static CRC_CCITT_FALSE: crc::Crc<u16> = crc::Crc::<u16>::new(&crc::CRC_16_IBM_3740);
+
+/// The CRC-16/CCITT-FALSE algorithm (also known as CRC-16/IBM-3740)
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CcittFalse;
+
+impl Crc16Variant for CcittFalse {
+    fn checksum(bytes: &[u8]) -> u16 {
+        CRC_CCITT_FALSE.checksum(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn bitwise_checksum_matches_check_value() {
+        assert_eq!(
+            bitwise_checksum(b"123456789"),
+            crc::CRC_16_OPENSAFETY_B.check
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn bitwise_checksum_matches_table(bytes in proptest::collection::vec(any::<u8>(), 0..300)) {
+            prop_assert_eq!(bitwise_checksum(&bytes), CRC.checksum(&bytes));
+        }
+    }
+}
@@ -0,0 +1,271 @@
+//! Canonical payload layout for telecommands.
+//!
+//! A command is serialized as a one byte opcode, followed by its arguments. The opcodes of the
+//! commands known to every device are:
+//! - `0x01` for [`Command::Ping`], without arguments
+//! - `0x02` for [`Command::Reset`], without arguments
+//! - `0x03` for [`Command::SetRate`], with the rate as a little endian `u16`
+//!
+//! Any other opcode is a device specific [`Command::Custom`], whose arguments are left
+//! uninterpreted.
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{DeviceId, Payload, TcPacket, Timestamp};
+
+/// Error that can occur when converting between a [`Command`] and a payload
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum CommandError {
+    /// The payload is empty, so it has no opcode
+    #[error("empty command payload")]
+    Empty,
+    /// The arguments don't have the size expected for the opcode
+    #[error(
+        "invalid arguments for opcode {opcode:#04x} (expected {expected} bytes, found {found})"
+    )]
+    InvalidArguments {
+        opcode: u8,
+        expected: usize,
+        found: usize,
+    },
+    /// A custom command uses the opcode of a known command, so it couldn't be told apart from it
+    #[error("opcode {0:#04x} is reserved")]
+    ReservedOpcode(u8),
+    /// The arguments of a custom command don't fit in a payload along with the opcode. The length
+    /// of the arguments is returned as the contents of this variant.
+    #[error("command arguments too long: {0} bytes")]
+    ArgumentsTooLong(usize),
+}
+
+/// A command sent to a device in a telecommand
+///
+/// # Example
+/// ```
+/// # use orbipacket::command::Command;
+/// let command = Command::SetRate(10);
+///
+/// let payload = command.to_payload()?;
+///
+/// assert_eq!(payload.as_bytes(), [0x03, 10, 0]);
+/// assert_eq!(Command::from_payload(&payload)?, command);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+// Boxing the arguments would require an allocator, and a command is no larger than a packet
+#[allow(clippy::large_enum_variant)]
+pub enum Command {
+    /// Check that the device is alive
+    Ping,
+    /// Restart the device
+    Reset,
+    /// Set the rate at which the device reports telemetry, in hertz
+    SetRate(u16),
+    /// A device specific command
+    Custom {
+        /// The opcode, which must not be that of a known command
+        opcode: u8,
+        /// The arguments, at most [`Payload::MAX_SIZE`] - 1 bytes long
+        args: Payload,
+    },
+}
+
+impl Command {
+    /// Opcode of [`Command::Ping`]
+    pub const PING: u8 = 0x01;
+    /// Opcode of [`Command::Reset`]
+    pub const RESET: u8 = 0x02;
+    /// Opcode of [`Command::SetRate`]
+    pub const SET_RATE: u8 = 0x03;
+
+    /// The opcode identifying the command
+    pub fn opcode(&self) -> u8 {
+        match self {
+            Command::Ping => Self::PING,
+            Command::Reset => Self::RESET,
+            Command::SetRate(_) => Self::SET_RATE,
+            Command::Custom { opcode, .. } => *opcode,
+        }
+    }
+
+    /// Serialize the command into a payload.
+    ///
+    /// # Errors
+    /// An error variant is returned for custom commands using the opcode of a known command, or
+    /// whose arguments are too long to fit in a payload.
+    pub fn to_payload(&self) -> Result<Payload, CommandError> {
+        let mut bytes = [0u8; Payload::MAX_SIZE];
+        bytes[0] = self.opcode();
+        let args = match self {
+            Command::Ping | Command::Reset => 0,
+            Command::SetRate(rate) => {
+                bytes[1..3].copy_from_slice(&rate.to_le_bytes());
+                2
+            }
+            Command::Custom { opcode, args } => {
+                if matches!(*opcode, Self::PING | Self::RESET | Self::SET_RATE) {
+                    return Err(CommandError::ReservedOpcode(*opcode));
+                }
+                let args = args.as_bytes();
+                bytes
+                    .get_mut(1..1 + args.len())
+                    .ok_or(CommandError::ArgumentsTooLong(args.len()))?
+                    .copy_from_slice(args);
+                args.len()
+            }
+        };
+
+        // Unwrapping is safe here because the bytes are at most Payload::MAX_SIZE long
+        Ok(Payload::from_raw_bytes(&bytes[..1 + args]).unwrap())
+    }
+
+    /// Read a command from a payload.
+    ///
+    /// # Errors
+    /// An error variant is returned if the payload is empty, or if the arguments of a known
+    /// command don't have the expected size.
+    pub fn from_payload(payload: &Payload) -> Result<Self, CommandError> {
+        let (opcode, args) = payload.split_first().ok_or(CommandError::Empty)?;
+        let expect_args = |expected: usize| {
+            if args.len() == expected {
+                Ok(())
+            } else {
+                Err(CommandError::InvalidArguments {
+                    opcode,
+                    expected,
+                    found: args.len(),
+                })
+            }
+        };
+
+        match opcode {
+            Self::PING => expect_args(0).map(|_| Command::Ping),
+            Self::RESET => expect_args(0).map(|_| Command::Reset),
+            // Unwrapping is safe here because the size of the arguments was checked
+            Self::SET_RATE => expect_args(2)
+                .map(|_| Command::SetRate(u16::from_le_bytes(args.try_into().unwrap()))),
+            // Unwrapping is safe here because the arguments are shorter than the payload
+            _ => Ok(Command::Custom {
+                opcode,
+                args: Payload::from_raw_bytes(args).unwrap(),
+            }),
+        }
+    }
+}
+
+/// # Commands
+impl TcPacket {
+    /// Create a new telecommand packet carrying the given command.
+    ///
+    /// # Errors
+    /// An error variant is returned if the command can't be serialized, see
+    /// [`Command::to_payload`].
+    ///
+    /// # Example
+    /// ```
+    /// # use orbipacket::{command::Command, DeviceId, TcPacket, Timestamp};
+    /// let packet = TcPacket::command(DeviceId::Camera, Timestamp::new(0)?, Command::Reset)?;
+    ///
+    /// assert_eq!(packet.parse_command()?, Command::Reset);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn command(
+        device_id: DeviceId,
+        timestamp: Timestamp,
+        command: Command,
+    ) -> Result<Self, CommandError> {
+        Ok(Self::new(device_id, timestamp, command.to_payload()?))
+    }
+
+    /// Read the command carried by the packet.
+    ///
+    /// # Errors
+    /// An error variant is returned if the payload doesn't hold a valid command, see
+    /// [`Command::from_payload`].
+    pub fn parse_command(&self) -> Result<Command, CommandError> {
+        Command::from_payload(self.payload())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payload(bytes: &[u8]) -> Payload {
+        Payload::from_raw_bytes(bytes).unwrap()
+    }
+
+    #[test]
+    fn known_commands_round_trip() {
+        for command in [Command::Ping, Command::Reset, Command::SetRate(0xABCD)] {
+            let payload = command.to_payload().unwrap();
+
+            assert_eq!(payload.first(), Some(command.opcode()));
+            assert_eq!(Command::from_payload(&payload), Ok(command));
+        }
+    }
+
+    #[test]
+    fn unknown_opcode_is_custom_command() {
+        let payload = payload(&[0x80, 1, 2, 3]);
+
+        let command = Command::from_payload(&payload).unwrap();
+
+        assert_eq!(
+            command,
+            Command::Custom {
+                opcode: 0x80,
+                args: Payload::from_raw_bytes([1, 2, 3]).unwrap()
+            }
+        );
+        assert_eq!(command.to_payload(), Ok(payload));
+    }
+
+    #[test]
+    fn from_payload_rejects_invalid_arguments() {
+        assert_eq!(
+            Command::from_payload(&Payload::new()),
+            Err(CommandError::Empty)
+        );
+        assert_eq!(
+            Command::from_payload(&payload(&[Command::PING, 0])),
+            Err(CommandError::InvalidArguments {
+                opcode: Command::PING,
+                expected: 0,
+                found: 1
+            })
+        );
+        assert_eq!(
+            Command::from_payload(&payload(&[Command::SET_RATE, 1])),
+            Err(CommandError::InvalidArguments {
+                opcode: Command::SET_RATE,
+                expected: 2,
+                found: 1
+            })
+        );
+    }
+
+    #[test]
+    fn to_payload_rejects_invalid_custom_commands() {
+        let reserved = Command::Custom {
+            opcode: Command::RESET,
+            args: Payload::new(),
+        };
+        let too_long = Command::Custom {
+            opcode: 0x80,
+            args: payload(&[0; Payload::MAX_SIZE]),
+        };
+
+        assert_eq!(
+            reserved.to_payload(),
+            Err(CommandError::ReservedOpcode(Command::RESET))
+        );
+        assert_eq!(
+            too_long.to_payload(),
+            Err(CommandError::ArgumentsTooLong(Payload::MAX_SIZE))
+        );
+    }
+}
@@ -0,0 +1,172 @@
+//! Recovery of frames corrupted by a single flipped bit.
+//!
+//! On links where errors are known to be isolated bit flips, a frame failing the CRC check can
+//! often be repaired instead of dropped. [`Packet::try_single_bit_correct`] looks for the one bit
+//! whose flip makes the CRC valid, and refuses to guess if there's more than one.
+
+use crate::checksum::Crc16Variant;
+use crate::decode::DecodeOptions;
+use crate::encode::crc_from_bytes;
+use crate::{InternalPacket, Packet};
+
+/// Check if the CRC at the end of an unstuffed frame matches its contents.
+fn crc_matches<C: Crc16Variant>(frame: &[u8]) -> bool {
+    let (contents, crc) = frame.split_at(frame.len() - InternalPacket::CRC_SIZE);
+    crc_from_bytes([crc[0], crc[1]]) == C::checksum(contents)
+}
+
+impl Packet {
+    /// Repair an unstuffed frame with an invalid CRC by flipping a single bit.
+    ///
+    /// `frame` holds the packet's fields and CRC, after COBS decoding, as returned in the first
+    /// slice of [`Packet::encode_parts`]. Every single bit flip is tried, and if exactly one of
+    /// them makes the CRC valid, it is applied to `frame` and the index of the flipped bit is
+    /// returned. Bit `i` is bit `i % 8` of byte `i / 8`, counting from the least significant bit.
+    ///
+    /// `None` is returned, leaving `frame` untouched, if the CRC is already valid, if no single
+    /// flip makes it valid, or if several do, since picking one of them could introduce an error
+    /// instead of fixing one. A repaired frame is only as trustworthy as the assumption that at
+    /// most one bit was flipped.
+    ///
+    /// # Example
+    /// ```
+    /// use orbipacket::{Packet, TmPacket, DeviceId, Timestamp, Payload};
+    ///
+    /// let packet = Packet::TmPacket(TmPacket::new(DeviceId::Gps, Timestamp::new(10)?, Payload::from_u8(7)));
+    /// let mut buffer = [0u8; Packet::MAX_ENCODE_BUFFER_SIZE];
+    /// let (raw, _) = packet.encode_parts(&mut buffer)?;
+    ///
+    /// let mut frame = raw.to_vec();
+    /// frame[8] ^= 1 << 3;
+    ///
+    /// assert_eq!(Packet::try_single_bit_correct(&mut frame), Some(8 * 8 + 3));
+    /// assert_eq!(frame, raw);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn try_single_bit_correct(frame: &mut [u8]) -> Option<usize> {
+        Self::try_single_bit_correct_with_options(frame, &DecodeOptions::new())
+    }
+
+    /// Repair an unstuffed frame with an invalid CRC by flipping a single bit, using the CRC
+    /// variant of the given options.
+    ///
+    /// See [`Self::try_single_bit_correct`] for details. Frames of a trusted link have no CRC,
+    /// so `None` is always returned if [`DecodeOptions::trusted_link`] is enabled.
+    pub fn try_single_bit_correct_with_options<C: Crc16Variant>(
+        frame: &mut [u8],
+        options: &DecodeOptions<C>,
+    ) -> Option<usize> {
+        if options.trusted_link
+            || frame.len() < InternalPacket::MIN_SIZE
+            || frame.len() > InternalPacket::MAX_SIZE
+            || crc_matches::<C>(frame)
+        {
+            return None;
+        }
+
+        let mut candidate = None;
+        for bit in 0..frame.len() * 8 {
+            let mask = 1 << (bit % 8);
+            frame[bit / 8] ^= mask;
+            let matches = crc_matches::<C>(frame);
+            frame[bit / 8] ^= mask;
+
+            if matches {
+                if candidate.is_some() {
+                    // Ambiguous, so nothing can be corrected safely
+                    return None;
+                }
+                candidate = Some(bit);
+            }
+        }
+
+        let bit = candidate?;
+        frame[bit / 8] ^= 1 << (bit % 8);
+        Some(bit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::checksum::Crc16Variant;
+    use crate::decode::DecodeOptions;
+    use crate::encode::crc_to_bytes;
+    use crate::{DeviceId, Packet, Payload, TcPacket, Timestamp};
+
+    /// A weak checksum, the XOR of all bytes, under which flipping the same bit of any byte has
+    /// the same effect, so single bit errors are always ambiguous
+    #[derive(Copy, Clone, Debug, Default)]
+    struct XorChecksum;
+
+    impl Crc16Variant for XorChecksum {
+        fn checksum(bytes: &[u8]) -> u16 {
+            bytes.iter().fold(0, |acc, &byte| acc ^ byte) as u16
+        }
+    }
+
+    fn raw_frame() -> Vec<u8> {
+        let packet = Packet::TcPacket(TcPacket::new(
+            DeviceId::Gps,
+            Timestamp::new(12345).unwrap(),
+            Payload::from_raw_bytes(b"arm deployment").unwrap(),
+        ));
+        let mut buffer = [0u8; Packet::MAX_ENCODE_BUFFER_SIZE];
+        packet.encode_parts(&mut buffer).unwrap().0.to_vec()
+    }
+
+    #[test]
+    fn corrects_every_single_bit_flip() {
+        let raw = raw_frame();
+        for bit in 0..raw.len() * 8 {
+            let mut frame = raw.clone();
+            frame[bit / 8] ^= 1 << (bit % 8);
+
+            assert_eq!(Packet::try_single_bit_correct(&mut frame), Some(bit));
+            assert_eq!(frame, raw);
+        }
+    }
+
+    #[test]
+    fn leaves_valid_frame_untouched() {
+        let raw = raw_frame();
+        let mut frame = raw.clone();
+
+        assert_eq!(Packet::try_single_bit_correct(&mut frame), None);
+        assert_eq!(frame, raw);
+    }
+
+    #[test]
+    fn refuses_to_correct_beyond_a_single_flip() {
+        let raw = raw_frame();
+        // Two bursts far apart can't be undone by any single flip
+        let mut frame = raw.clone();
+        frame[3] ^= 0xFF;
+        frame[10] ^= 0xFF;
+        let corrupt = frame.clone();
+
+        assert_eq!(Packet::try_single_bit_correct(&mut frame), None);
+        assert_eq!(frame, corrupt);
+    }
+
+    #[test]
+    fn refuses_to_correct_ambiguous_flip() {
+        let mut frame = raw_frame();
+        let crc_offset = frame.len() - 2;
+        let checksum = XorChecksum::checksum(&frame[..crc_offset]);
+        frame[crc_offset..].copy_from_slice(&crc_to_bytes(checksum));
+        frame[5] ^= 1 << 3;
+        let corrupt = frame.clone();
+        let options = DecodeOptions::new().crc_variant::<XorChecksum>();
+
+        assert_eq!(
+            Packet::try_single_bit_correct_with_options(&mut frame, &options),
+            None
+        );
+        assert_eq!(frame, corrupt);
+    }
+
+    #[test]
+    fn ignores_frames_of_impossible_length() {
+        assert_eq!(Packet::try_single_bit_correct(&mut [1, 2, 3]), None);
+    }
+}
@@ -1,8 +1,33 @@
+use core::{
+    marker::PhantomData,
+    ops::{Range, RangeInclusive},
+};
+
 use crate::{
-    device_id::DeviceIdError, encode::CRC, InternalPacket, Packet, Payload, TcPacket, Timestamp,
-    TmPacket, VERSION,
+    checksum::{Crc16Variant, OpenSafetyB},
+    device_id::DeviceIdError,
+    encode::{crc_from_bytes, LengthPrefix},
+    payload::PayloadError,
+    DeviceId, InternalPacket, Packet, Payload, ProtocolVersion, TcPacket, Timestamp,
+    TimestampError, TmPacket,
 };
 
+/// Emit a `log::trace!` message, if the `log` feature is enabled
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "log")]
+        log::trace!($($arg)*);
+    };
+}
+
+/// Emit a `log::debug!` message, if the `log` feature is enabled
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "log")]
+        log::debug!($($arg)*);
+    };
+}
+
 #[derive(thiserror::Error, Debug)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum DecodeError {
@@ -10,14 +35,431 @@ pub enum DecodeError {
     Cobs(#[from] cobs::DecodeError),
     #[error("buffer too short to hold a complete packet ({0} bytes long)")]
     BufferTooShort(usize),
+    #[error("frame too long to hold a single packet ({0} bytes long)")]
+    FrameTooLong(usize),
+    #[error("no frame terminator found in {0} bytes")]
+    UnterminatedFrame(usize),
     #[error("unsupported protocol version ({0})")]
     UnsupportedVersion(u8),
+    #[error("protocol version {0} is newer than the supported versions")]
+    NewerVersion(u8),
     #[error("invalid packet checksum (expected {expected}, found {found})")]
     InvalidChecksum { expected: u16, found: u16 },
     #[error("invalid packet length (expected {expected}, found {found})")]
     InvalidLength { expected: usize, found: usize },
+    #[error("reserved bits of the control byte are set ({0:#04b})")]
+    ReservedBitsSet(u8),
+    #[error("frame carries a checksum, but a trusted link frame was expected")]
+    UnexpectedChecksum,
+    #[error("packet from unexpected device ({0})")]
+    UnexpectedDevice(DeviceId),
+    #[error("unexpected payload length for {device} ({length} bytes)")]
+    SuspiciousLength { device: DeviceId, length: usize },
     #[error(transparent)]
     IdError(#[from] DeviceIdError),
+    #[error(transparent)]
+    TimestampError(#[from] TimestampError),
+    #[error(transparent)]
+    PayloadError(#[from] PayloadError),
+    #[cfg(feature = "base64")]
+    #[error("invalid base64 text")]
+    InvalidBase64,
+}
+
+/// Number of frames rejected by a lenient decoder, by kind of error
+///
+/// The kinds of errors point at different problems with the link: COBS and length errors are
+/// usually caused by dropped bytes, while CRC errors are caused by flipped bits. Statistics of
+/// successive calls can be accumulated with `+=`.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DecodeStats {
+    /// Frames which aren't valid COBS
+    pub cobs_errors: usize,
+    /// Frames too short or too long, or whose length doesn't match their header
+    pub length_errors: usize,
+    /// Frames whose checksum doesn't match their contents
+    pub crc_errors: usize,
+    /// Frames with an unsupported protocol version
+    pub version_errors: usize,
+    /// Frames rejected for any other reason, such as an invalid device ID
+    pub other_errors: usize,
+}
+
+impl DecodeStats {
+    /// Total number of rejected frames
+    pub fn total_errors(&self) -> usize {
+        self.cobs_errors
+            + self.length_errors
+            + self.crc_errors
+            + self.version_errors
+            + self.other_errors
+    }
+
+    /// Count a frame rejected with the given error.
+    fn record(&mut self, error: &DecodeError) {
+        let counter = match error {
+            DecodeError::Cobs(_) => &mut self.cobs_errors,
+            DecodeError::BufferTooShort(_)
+            | DecodeError::FrameTooLong(_)
+            | DecodeError::UnterminatedFrame(_)
+            | DecodeError::InvalidLength { .. }
+            | DecodeError::UnexpectedChecksum => &mut self.length_errors,
+            DecodeError::InvalidChecksum { .. } => &mut self.crc_errors,
+            DecodeError::UnsupportedVersion(_) | DecodeError::NewerVersion(_) => {
+                &mut self.version_errors
+            }
+            DecodeError::ReservedBitsSet(_)
+            | DecodeError::UnexpectedDevice(_)
+            | DecodeError::SuspiciousLength { .. }
+            | DecodeError::IdError(_)
+            | DecodeError::TimestampError(_)
+            | DecodeError::PayloadError(_) => &mut self.other_errors,
+            #[cfg(feature = "base64")]
+            DecodeError::InvalidBase64 => &mut self.other_errors,
+        };
+        *counter += 1;
+    }
+}
+
+impl core::ops::AddAssign for DecodeStats {
+    fn add_assign(&mut self, other: Self) {
+        self.cobs_errors += other.cobs_errors;
+        self.length_errors += other.length_errors;
+        self.crc_errors += other.crc_errors;
+        self.version_errors += other.version_errors;
+        self.other_errors += other.other_errors;
+    }
+}
+
+/// Link health metrics, accumulated over the lifetime of a link
+///
+/// Unlike [`DecodeStats`], which describes a single call, metrics are owned by the caller and
+/// updated in place by every call to [`Packet::decode_stateless_lenient_with_metrics`], so that
+/// they describe everything received since they were created. This makes them suitable for live
+/// monitoring, e.g. to compute a rolling frame error rate from two snapshots.
+///
+/// # Example
+/// ```
+/// use orbipacket::{decode::DecodeMetrics, Packet, TmPacket, DeviceId, Timestamp, Payload};
+///
+/// let packet = Packet::TmPacket(TmPacket::new(DeviceId::Gps, Timestamp::new(10)?, Payload::from_u8(7)));
+/// let mut encode_buffer = [0u8; Packet::MAX_ENCODE_BUFFER_SIZE];
+/// let encoded = packet.encode(&mut encode_buffer)?;
+/// let mut metrics = DecodeMetrics::default();
+/// let mut out = [packet; 4];
+///
+/// for corrupt in [false, true] {
+///     let mut buf = [0u8; 64];
+///     buf[..encoded.len()].copy_from_slice(encoded);
+///     if corrupt {
+///         buf[3] ^= 0x10;
+///     }
+///     Packet::decode_stateless_lenient_with_metrics(&mut buf[..encoded.len()], &mut out, &mut metrics);
+/// }
+///
+/// assert_eq!(metrics.frames_seen, 2);
+/// assert_eq!(metrics.frames_decoded, 1);
+/// assert_eq!(metrics.errors.crc_errors, 1);
+/// assert_eq!(metrics.frame_error_rate(), 0.5);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DecodeMetrics {
+    /// Non-empty frames received, whether they could be decoded or not
+    pub frames_seen: u64,
+    /// Frames successfully decoded into packets
+    pub frames_decoded: u64,
+    /// Bytes consumed, including delimiters and empty frames
+    pub bytes_processed: u64,
+    /// Frames rejected, by kind of error
+    pub errors: DecodeStats,
+}
+
+impl DecodeMetrics {
+    /// Record the outcome of decoding a frame which occupied `len` bytes, including its
+    /// delimiter.
+    ///
+    /// This is called by the decoders taking metrics, but can also be used to track frames
+    /// decoded by other means.
+    pub fn accumulate<T>(&mut self, len: usize, result: &Result<T, DecodeError>) {
+        self.frames_seen += 1;
+        self.bytes_processed += len as u64;
+        match result {
+            Ok(_) => self.frames_decoded += 1,
+            Err(error) => self.errors.record(error),
+        }
+    }
+
+    /// Fraction of the frames seen which couldn't be decoded, or 0 if no frames were seen
+    pub fn frame_error_rate(&self) -> f32 {
+        if self.frames_seen == 0 {
+            return 0.0;
+        }
+        (self.frames_seen - self.frames_decoded) as f32 / self.frames_seen as f32
+    }
+}
+
+/// Options controlling how packets are decoded
+///
+/// The default options are the strictest, rejecting anything that doesn't fully adhere to a
+/// supported version of the protocol.
+///
+/// # Example
+/// ```
+/// # use orbipacket::decode::DecodeOptions;
+/// let options = DecodeOptions::new().lenient_version(true);
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub struct DecodeOptions<C = OpenSafetyB> {
+    lenient_version: bool,
+    allow_reserved_bits: bool,
+    pub(crate) trusted_link: bool,
+    /// Bitmask of the accepted device IDs, or `None` to accept all of them
+    allowed_devices: Option<u32>,
+    length_prefix: LengthPrefix,
+    max_frames: Option<usize>,
+    max_bytes: Option<usize>,
+    /// Bounds of the expected payload length of each device, indexed by device ID
+    expected_payload_lens: [Option<(u8, u8)>; 16],
+    crc_variant: PhantomData<C>,
+}
+
+impl DecodeOptions {
+    /// Create the default, strict, decode options.
+    pub const fn new() -> Self {
+        DecodeOptions {
+            lenient_version: false,
+            allow_reserved_bits: false,
+            trusted_link: false,
+            allowed_devices: None,
+            length_prefix: LengthPrefix::U16,
+            max_frames: None,
+            max_bytes: None,
+            expected_payload_lens: [None; 16],
+            crc_variant: PhantomData,
+        }
+    }
+}
+
+impl<C: Crc16Variant> DecodeOptions<C> {
+    /// Use the given CRC algorithm, instead of the one specified by the protocol.
+    ///
+    /// This must match the variant the packets were encoded with, set using
+    /// [`EncodeOptions::crc_variant`](crate::encode::EncodeOptions::crc_variant).
+    ///
+    /// # Example
+    /// ```
+    /// # use orbipacket::{checksum::CcittFalse, decode::DecodeOptions};
+    /// let options = DecodeOptions::new().crc_variant::<CcittFalse>();
+    /// ```
+    pub const fn crc_variant<V: Crc16Variant>(self) -> DecodeOptions<V> {
+        DecodeOptions {
+            lenient_version: self.lenient_version,
+            allow_reserved_bits: self.allow_reserved_bits,
+            trusted_link: self.trusted_link,
+            allowed_devices: self.allowed_devices,
+            length_prefix: self.length_prefix,
+            max_frames: self.max_frames,
+            max_bytes: self.max_bytes,
+            expected_payload_lens: self.expected_payload_lens,
+            crc_variant: PhantomData,
+        }
+    }
+
+    /// Whether to decode packets with a protocol version newer than the supported ones.
+    ///
+    /// When enabled, such packets are decoded assuming the layout of the first version of the
+    /// protocol, and keep the observed version as [`ProtocolVersion::Newer`]. Decoding
+    /// still fails if the packet doesn't fit that layout, e.g. if the length or CRC don't match.
+    pub const fn lenient_version(self, lenient: bool) -> Self {
+        DecodeOptions {
+            lenient_version: lenient,
+            ..self
+        }
+    }
+
+    /// Whether to accept packets with the reserved bits of the control byte set.
+    ///
+    /// When enabled, the reserved bits are kept in the decoded packet, see
+    /// [`Packet::control_flags`]. Otherwise, such packets are rejected, since they may be corrupt
+    /// or follow a newer format.
+    pub const fn allow_reserved_bits(self, allow: bool) -> Self {
+        DecodeOptions {
+            allow_reserved_bits: allow,
+            ..self
+        }
+    }
+
+    /// Whether to expect packets without a CRC, as encoded with
+    /// [`EncodeOptions::trusted_link`](crate::encode::EncodeOptions::trusted_link).
+    ///
+    /// The wire format differs between both modes, so this must match the encoder's setting.
+    /// Packets with a CRC are rejected in this mode with [`DecodeError::UnexpectedChecksum`],
+    /// while packets without one fail the length check in the default mode.
+    pub const fn trusted_link(self, trusted: bool) -> Self {
+        DecodeOptions {
+            trusted_link: trusted,
+            ..self
+        }
+    }
+
+    /// Width of the length prefix read by [`Packet::decode_raw_with_options`].
+    ///
+    /// This has no effect on COBS framed packets, and must match the width used by the encoder,
+    /// set using [`EncodeOptions::length_prefix`](crate::encode::EncodeOptions::length_prefix).
+    pub const fn length_prefix(self, length_prefix: LengthPrefix) -> Self {
+        DecodeOptions {
+            length_prefix,
+            ..self
+        }
+    }
+
+    /// Maximum number of frames processed by a call to
+    /// [`Packet::decode_stateless_with_options`], or `None` for no limit.
+    ///
+    /// Together with [`Self::max_bytes`], this bounds the work done per call, for real-time
+    /// callers receiving untrusted data, e.g. a buffer full of tiny frames.
+    pub const fn max_frames(self, max_frames: Option<usize>) -> Self {
+        DecodeOptions { max_frames, ..self }
+    }
+
+    /// Maximum number of bytes processed by a call to
+    /// [`Packet::decode_stateless_with_options`], or `None` for no limit.
+    ///
+    /// Frames are only decoded if they fit entirely, terminator included, within the budget.
+    pub const fn max_bytes(self, max_bytes: Option<usize>) -> Self {
+        DecodeOptions { max_bytes, ..self }
+    }
+
+    /// Only accept packets from the given device whose payload length is within `range`.
+    ///
+    /// A payload length outside the range a device is known to send, e.g. an empty payload from a
+    /// sensor, may reveal corruption that slipped past the CRC. Such packets are rejected with
+    /// [`DecodeError::SuspiciousLength`]. By default, any payload length is accepted, and calling
+    /// this again for the same device replaces its range.
+    ///
+    /// # Example
+    /// ```
+    /// # use orbipacket::{decode::DecodeOptions, DeviceId};
+    /// let options = DecodeOptions::new()
+    ///     .expected_payload_len(DeviceId::Gps, 1..=16)
+    ///     .expected_payload_len(DeviceId::Altimeter, 4..=4);
+    /// ```
+    pub const fn expected_payload_len(
+        self,
+        device: DeviceId,
+        range: RangeInclusive<usize>,
+    ) -> Self {
+        // Lengths are clamped to the range of the length byte, which they can't exceed anyway
+        let start = if *range.start() > u8::MAX as usize {
+            u8::MAX
+        } else {
+            *range.start() as u8
+        };
+        let end = if *range.end() > u8::MAX as usize {
+            u8::MAX
+        } else {
+            *range.end() as u8
+        };
+
+        let mut expected_payload_lens = self.expected_payload_lens;
+        expected_payload_lens[device.as_u8() as usize] = Some((start, end));
+        DecodeOptions {
+            expected_payload_lens,
+            ..self
+        }
+    }
+
+    /// Only accept packets from the given devices.
+    ///
+    /// Packets from other devices are rejected with [`DecodeError::UnexpectedDevice`], which
+    /// helps catch misrouted or spoofed frames. By default, packets from any device are accepted.
+    ///
+    /// # Example
+    /// ```
+    /// # use orbipacket::{decode::DecodeOptions, DeviceId};
+    /// let options = DecodeOptions::new().allowed_devices(&[DeviceId::System, DeviceId::Gps]);
+    /// ```
+    pub const fn allowed_devices(self, devices: &[DeviceId]) -> Self {
+        let mut mask = 0u32;
+        let mut idx = 0;
+        while idx < devices.len() {
+            mask |= 1 << devices[idx].as_u8();
+            idx += 1;
+        }
+
+        DecodeOptions {
+            allowed_devices: Some(mask),
+            ..self
+        }
+    }
+}
+
+/// Header of a decoded packet
+///
+/// This holds every field of a packet except the payload, which is left in the decoded buffer.
+/// It is obtained with [`Packet::peek_header`], and is useful to make decisions about a packet
+/// (e.g. routing it) without copying its payload.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PacketHeader {
+    version: ProtocolVersion,
+    device_id: DeviceId,
+    is_tm_packet: bool,
+    sequence: Option<u16>,
+    timestamp: Timestamp,
+    control_flags: u8,
+    payload_offset: usize,
+    payload_length: usize,
+}
+
+impl PacketHeader {
+    /// Protocol version of the packet
+    pub fn version(&self) -> ProtocolVersion {
+        self.version
+    }
+
+    /// ID of the device the packet refers to
+    pub fn device_id(&self) -> &DeviceId {
+        &self.device_id
+    }
+
+    /// Check if the packet is a telemetry packet
+    pub fn is_tm_packet(&self) -> bool {
+        self.is_tm_packet
+    }
+
+    /// Check if the packet is a telecommand packet
+    pub fn is_tc_packet(&self) -> bool {
+        !self.is_tm_packet
+    }
+
+    /// Sequence number of the packet, if it has one
+    pub fn sequence(&self) -> Option<u16> {
+        self.sequence
+    }
+
+    /// Timestamp of the packet
+    pub fn timestamp(&self) -> &Timestamp {
+        &self.timestamp
+    }
+
+    /// Reserved low bits of the control byte, see [`Packet::control_flags`]
+    pub fn control_flags(&self) -> u8 {
+        self.control_flags
+    }
+
+    /// Length of the payload, in bytes
+    pub fn payload_length(&self) -> usize {
+        self.payload_length
+    }
+
+    /// Range of the decoded buffer holding the payload
+    pub fn payload_range(&self) -> Range<usize> {
+        self.payload_offset..self.payload_offset + self.payload_length
+    }
 }
 
 impl Packet {
@@ -31,122 +473,864 @@ impl Packet {
     /// An error variant is returned if the provided bytes do not constitute a valid packet.
     /// Namely, the following conditions result in errors:
     /// - the bytes are not a valid COBS frame;
-    /// - the (unstuffed) buffer is shorter than 13 bytes;
-    /// - the packet's version isn't supported;
+    /// - the (unstuffed) buffer is shorter than [`TmPacket::MIN_SIZE`] bytes, the size of a packet
+    ///   with an empty payload, or longer than [`TmPacket::MAX_SIZE`] bytes;
+    /// - the packet's version isn't supported ([`DecodeError::NewerVersion`] is returned for
+    ///   versions newer than the supported ones, which may be accepted using
+    ///   [`Self::decode_single_with_options`]);
+    /// - the buffer is too short to hold the header of the packet's version;
     /// - the reported payload length doesn't match it's actual length;
     /// - the CRC checksum is incorrect;
-    /// - the control byte cannot be properly parsed into a device ID.
+    /// - the reserved bits of the control byte are set (which may be allowed using
+    ///   [`Self::decode_single_with_options`]);
+    /// - the control byte cannot be properly parsed into a device ID;
+    /// - the timestamp or payload are out of the range allowed by the protocol.
     ///
     /// # Examples
     /// ```
-    /// use orbipacket::{Packet, DeviceId};
+    /// use orbipacket::{Packet, DeviceId, ProtocolVersion};
     ///
     /// let mut buf = [
     ///     0x05, 1, 0x04, 0x04, 0x0a, 0x01, 0x01, 0x01, 0x04, 0xEF, 0xCD, 0xAB, 0x03, 0x7e, 0x12, 0,
     /// ];
+    /// # #[cfg(feature = "crc-be")]
+    /// # buf.swap(13, 14);
     ///
     /// let packet = Packet::decode_single(&mut buf)?;
     ///
     /// let Packet::TmPacket(packet) = packet else {
     ///     panic!("Decoded packet is not TmPacket")
     /// };
-    /// assert_eq!(packet.version(), 1);
+    /// assert_eq!(packet.version(), ProtocolVersion::V1);
     /// assert_eq!(packet.device_id(), &DeviceId::TimeSync);
     /// assert_eq!(packet.timestamp().get(), 10);
     /// assert_eq!(packet.payload().as_bytes(), [0xEF, 0xCD, 0xAB, 0]);
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
     pub fn decode_single(buf: &mut [u8]) -> Result<Self, DecodeError> {
-        let len = cobs::decode_in_place(buf)?;
+        Self::decode_single_with_options(buf, &DecodeOptions::new())
+    }
+
+    /// Decode a buffer containing a single packet, using the given options.
+    ///
+    /// See [`Self::decode_single`] for details.
+    ///
+    /// # Examples
+    /// ```
+    /// use orbipacket::{Packet, ProtocolVersion, decode::{DecodeError, DecodeOptions}};
+    ///
+    /// // A packet with version 7, which is newer than the supported versions
+    /// let mut frame = [
+    ///     0x05, 7, 0x04, 0x04, 0x0a, 0x01, 0x01, 0x01, 0x04, 0xEF, 0xCD, 0xAB, 0x03, 0x1a, 0x30, 0,
+    /// ];
+    /// # #[cfg(feature = "crc-be")]
+    /// # frame.swap(13, 14);
+    ///
+    /// let result = Packet::decode_single(&mut frame.clone());
+    /// assert!(matches!(result, Err(DecodeError::NewerVersion(7))));
+    ///
+    /// let options = DecodeOptions::new().lenient_version(true);
+    /// let packet = Packet::decode_single_with_options(&mut frame.clone(), &options)?;
+    /// let Packet::TmPacket(packet) = packet else {
+    ///     panic!("Decoded packet is not TmPacket")
+    /// };
+    /// assert_eq!(packet.version(), ProtocolVersion::Newer(7));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn decode_single_with_options<C: Crc16Variant>(
+        buf: &mut [u8],
+        options: &DecodeOptions<C>,
+    ) -> Result<Self, DecodeError> {
+        let header = Self::peek_header_with_options(buf, options)?;
+        Self::from_header(&header, buf)
+    }
 
-        if len < InternalPacket::OVERHEAD {
+    /// Build a packet from its parsed header and the unstuffed buffer it was parsed from.
+    fn from_header(header: &PacketHeader, buf: &[u8]) -> Result<Self, DecodeError> {
+        // Indexing is safe here because the payload range was bounds checked with the header
+        let mut packet = InternalPacket::new(
+            header.device_id,
+            header.timestamp,
+            Payload::from_raw_bytes(&buf[header.payload_range()])?,
+        );
+        if let Some(sequence) = header.sequence {
+            packet = packet.with_sequence(sequence);
+        }
+        packet.version = header.version;
+        packet.control_flags = header.control_flags;
+
+        Ok(if header.is_tm_packet {
+            Self::TmPacket(TmPacket(packet))
+        } else {
+            Self::TcPacket(TcPacket(packet))
+        })
+    }
+
+    /// Decode and validate the header of a buffer containing a single packet, without reading
+    /// its payload.
+    ///
+    /// The buffer is unstuffed in-place, exactly as with [`Self::decode_single`], and undergoes
+    /// the same validations, including the CRC check. Once decoded, the payload can be accessed
+    /// without copying it through [`PacketHeader::payload_range`].
+    ///
+    /// # Errors
+    /// The same errors as [`Self::decode_single`] are returned, except for the ones related to
+    /// the payload.
+    ///
+    /// # Examples
+    /// ```
+    /// use orbipacket::{Packet, DeviceId};
+    ///
+    /// let mut buf = [
+    ///     0x05, 1, 0x04, 0x04, 0x0a, 0x01, 0x01, 0x01, 0x04, 0xEF, 0xCD, 0xAB, 0x03, 0x7e, 0x12, 0,
+    /// ];
+    /// # #[cfg(feature = "crc-be")]
+    /// # buf.swap(13, 14);
+    ///
+    /// let header = Packet::peek_header(&mut buf)?;
+    ///
+    /// assert!(header.is_tm_packet());
+    /// assert_eq!(header.device_id(), &DeviceId::TimeSync);
+    /// assert_eq!(header.timestamp().get(), 10);
+    /// assert_eq!(header.payload_length(), 4);
+    /// assert_eq!(buf[header.payload_range()], [0xEF, 0xCD, 0xAB, 0]);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn peek_header(buf: &mut [u8]) -> Result<PacketHeader, DecodeError> {
+        Self::peek_header_with_options(buf, &DecodeOptions::new())
+    }
+
+    /// Decode and validate the header of a buffer containing a single packet, using the given
+    /// options.
+    ///
+    /// See [`Self::peek_header`] for details.
+    pub fn peek_header_with_options<C: Crc16Variant>(
+        buf: &mut [u8],
+        options: &DecodeOptions<C>,
+    ) -> Result<PacketHeader, DecodeError> {
+        #[cfg(feature = "log")]
+        let frame_len = buf.len();
+        let len = cobs::decode_in_place(buf).inspect_err(|_error| {
+            debug!("COBS decoding of {frame_len} byte frame failed: {_error:?}");
+        })?;
+        trace!("COBS decoded {frame_len} byte frame into {len} bytes");
+
+        // COBS never decodes to more bytes than it was given, but all the indexing below relies on
+        // the frame fitting in the buffer, so this is checked rather than assumed
+        let buf = buf.get(..len).ok_or(DecodeError::FrameTooLong(len))?;
+        Self::parse_header(buf, options)
+    }
+
+    /// Check if a frame holds a valid packet, without building the packet.
+    ///
+    /// This runs the same validations as [`Self::decode_single`], including the CRC check, and
+    /// returns `true` only if the frame would decode successfully. It is meant to cheaply discard
+    /// garbage before queuing frames for a full decode.
+    ///
+    /// Like [`Self::decode_single`], this unstuffs the frame in-place, so the original encoded
+    /// bytes are lost. Use [`Self::is_valid_frame_copied`] to keep them.
+    ///
+    /// # Example
+    /// ```
+    /// use orbipacket::Packet;
+    ///
+    /// let frame = [
+    ///     0x05, 1, 0x04, 0x04, 0x0a, 0x01, 0x01, 0x01, 0x04, 0xEF, 0xCD, 0xAB, 0x03, 0x7e, 0x12, 0,
+    /// ];
+    /// # #[cfg(feature = "crc-be")]
+    /// # let frame = { let mut frame = frame; frame.swap(13, 14); frame };
+    ///
+    /// assert!(Packet::is_valid_frame(&mut frame.clone()));
+    ///
+    /// let mut corrupt = frame;
+    /// corrupt[9] ^= 0x01;
+    /// assert!(!Packet::is_valid_frame(&mut corrupt));
+    /// ```
+    pub fn is_valid_frame(frame: &mut [u8]) -> bool {
+        // The payload can't fail to be built once the header is valid, since its length is
+        // bounded by the length byte
+        Self::peek_header(frame).is_ok()
+    }
+
+    /// Check if a frame holds a valid packet, without building the packet nor modifying the
+    /// frame.
+    ///
+    /// The frame is copied to a scratch buffer before being checked with
+    /// [`Self::is_valid_frame`]. Frames longer than [`TmPacket::MAX_ENCODED_SIZE`] are invalid.
+    pub fn is_valid_frame_copied(frame: &[u8]) -> bool {
+        let mut scratch = [0u8; InternalPacket::MAX_ENCODED_SIZE];
+        match scratch.get_mut(..frame.len()) {
+            Some(scratch) => {
+                scratch.copy_from_slice(frame);
+                Self::is_valid_frame(scratch)
+            }
+            None => false,
+        }
+    }
+
+    /// Parse and validate the header of an unstuffed packet, spanning the whole buffer.
+    fn parse_header<C: Crc16Variant>(
+        buf: &[u8],
+        options: &DecodeOptions<C>,
+    ) -> Result<PacketHeader, DecodeError> {
+        let len = buf.len();
+        if len > InternalPacket::MAX_SIZE {
+            debug!("decoded frame too long ({len} bytes)");
+            return Err(DecodeError::FrameTooLong(len));
+        }
+
+        let crc_size = if options.trusted_link {
+            0
+        } else {
+            InternalPacket::CRC_SIZE
+        };
+        if len < InternalPacket::HEADER_SIZE + crc_size {
+            debug!("decoded frame too short ({len} bytes)");
             return Err(DecodeError::BufferTooShort(len));
         }
 
-        if buf[0] != VERSION {
-            return Err(DecodeError::UnsupportedVersion(buf[0]));
+        let v = buf[0];
+        trace!("observed protocol version {v}");
+        let version = match ProtocolVersion::try_from(v) {
+            Ok(version) => version,
+            Err(_) if v > ProtocolVersion::LATEST.as_u8() && options.lenient_version => {
+                ProtocolVersion::Newer(v)
+            }
+            Err(_) if v > ProtocolVersion::LATEST.as_u8() => {
+                debug!("rejecting newer protocol version {v}");
+                return Err(DecodeError::NewerVersion(v));
+            }
+            Err(_) => {
+                debug!("rejecting unsupported protocol version {v}");
+                return Err(DecodeError::UnsupportedVersion(v));
+            }
+        };
+        let sequence_size = match version {
+            ProtocolVersion::V2 => InternalPacket::SEQUENCE_SIZE,
+            ProtocolVersion::V1 | ProtocolVersion::Newer(_) => 0,
+        };
+
+        let header_size = InternalPacket::HEADER_SIZE + sequence_size;
+        if len < header_size + crc_size {
+            debug!("decoded frame too short for version {version} ({len} bytes)");
+            return Err(DecodeError::BufferTooShort(len));
         }
 
         let found_payload_len = buf[1] as usize;
-        let expected_payload_len = len - InternalPacket::OVERHEAD;
+        // The payload sits between the header and the CRC, so its length is fully determined by
+        // the length of the unstuffed frame
+        let expected_payload_len = len - header_size - crc_size;
+        trace!("payload length: expected {expected_payload_len}, found {found_payload_len}");
+        if options.trusted_link
+            && found_payload_len + InternalPacket::CRC_SIZE == expected_payload_len
+        {
+            debug!("frame has room for a checksum, but a trusted link frame was expected");
+            return Err(DecodeError::UnexpectedChecksum);
+        }
         if found_payload_len != expected_payload_len {
+            debug!("payload length mismatch: expected {expected_payload_len}, found {found_payload_len}");
             return Err(DecodeError::InvalidLength {
                 expected: expected_payload_len,
                 found: found_payload_len,
             });
         }
 
-        let found_checksum = u16::from_le_bytes([buf[len - 2], buf[len - 1]]);
-        let expected_checksum = CRC.checksum(&buf[..len - 2]);
+        if !options.trusted_link {
+            let found_checksum = crc_from_bytes([buf[len - 2], buf[len - 1]]);
+            let expected_checksum = C::checksum(&buf[..len - 2]);
+            trace!("checksum: expected {expected_checksum:#06x}, found {found_checksum:#06x}");
 
-        if found_checksum != expected_checksum {
-            return Err(DecodeError::InvalidChecksum {
-                expected: expected_checksum,
-                found: found_checksum,
-            });
+            if found_checksum != expected_checksum {
+                debug!(
+                    "checksum mismatch: expected {expected_checksum:#06x}, found {found_checksum:#06x}"
+                );
+                return Err(DecodeError::InvalidChecksum {
+                    expected: expected_checksum,
+                    found: found_checksum,
+                });
+            }
+        }
+
+        let reserved = buf[2] & InternalPacket::CONTROL_FLAGS_MASK;
+        if reserved != 0 && !options.allow_reserved_bits {
+            debug!("reserved bits of control byte set ({reserved:#04b})");
+            return Err(DecodeError::ReservedBitsSet(reserved));
         }
 
         let tmtc = (buf[2] & 1 << 7) == 0;
         let id = (buf[2] & 0b01111100) >> 2;
+        trace!(
+            "parsed control byte {:#04x}: device id {id}, {} packet",
+            buf[2],
+            if tmtc { "telemetry" } else { "telecommand" }
+        );
+        let sequence = (sequence_size != 0).then(|| u16::from_le_bytes([buf[3], buf[4]]));
+        let ts = header_size - 5;
         // A range can't be used here because from_le_bytes expects a [u8; 8]
-        let timestamp = u64::from_le_bytes([buf[3], buf[4], buf[5], buf[6], buf[7], 0, 0, 0]);
+        let timestamp = u64::from_le_bytes([
+            buf[ts],
+            buf[ts + 1],
+            buf[ts + 2],
+            buf[ts + 3],
+            buf[ts + 4],
+            0,
+            0,
+            0,
+        ]);
 
-        let packet = InternalPacket::new(
-            id.try_into()?,
-            // Unwrapping is safe here because we just created the value from 5 bytes
-            Timestamp::new(timestamp).unwrap(),
-            // Unwrapping is safe here because found_payload_len is at most 255, so the slice
-            // is never too long for Payload
-            Payload::from_raw_bytes(&buf[8..][..found_payload_len]).unwrap(),
-        );
+        // The length was validated above, but the range is still bounds checked so that a corrupt
+        // length byte can never cause a panic
+        let payload_offset = header_size;
+        if payload_offset + found_payload_len > len - crc_size {
+            return Err(DecodeError::InvalidLength {
+                expected: expected_payload_len,
+                found: found_payload_len,
+            });
+        }
 
-        Ok(if tmtc {
-            Self::TmPacket(TmPacket(packet))
-        } else {
-            Self::TcPacket(TcPacket(packet))
+        let device_id = DeviceId::try_from(id)?;
+        if options
+            .allowed_devices
+            .is_some_and(|mask| mask & 1 << id == 0)
+        {
+            debug!("rejecting packet from unexpected device {device_id}");
+            return Err(DecodeError::UnexpectedDevice(device_id));
+        }
+        if let Some((min, max)) = options.expected_payload_lens[device_id.as_u8() as usize] {
+            if !(min as usize..=max as usize).contains(&found_payload_len) {
+                debug!("rejecting {found_payload_len} byte payload from {device_id}");
+                return Err(DecodeError::SuspiciousLength {
+                    device: device_id,
+                    length: found_payload_len,
+                });
+            }
+        }
+
+        Ok(PacketHeader {
+            version,
+            device_id,
+            is_tm_packet: tmtc,
+            sequence,
+            timestamp: Timestamp::new(timestamp)?,
+            control_flags: reserved,
+            payload_offset,
+            payload_length: found_payload_len,
         })
     }
 
-    pub fn decode_stateless<'a, 'b>(
-        mut buf: &'a mut [u8],
-        out: &'b mut [Self],
-    ) -> Result<(&'a mut [u8], &'b mut [Self]), DecodeError> {
-        let mut out_idx: usize = 0;
-
-        while let Some(idx) = buf.iter().position(|&x| x == 0) {
-            if out_idx >= out.len() {
-                // Decrement out_idx so output subslice is correct
-                out_idx -= 1;
-                break;
-            }
+    /// Decode a packet prefixed by its length, as encoded with [`Packet::encode_raw`].
+    ///
+    /// The buffer may hold more data after the packet. The decoded packet is returned along with
+    /// the number of bytes it occupied, prefix included, so that the next packet can be decoded
+    /// from the rest of the buffer. Unlike with [`Self::decode_single`], the buffer isn't modified.
+    ///
+    /// # Errors
+    /// An error variant is returned if the buffer is shorter than the length prefix, or than the
+    /// length it reports. Otherwise, the packet undergoes the same validations as with
+    /// [`Self::decode_single`], besides the COBS decoding.
+    ///
+    /// # Example
+    /// ```
+    /// use orbipacket::{Packet, TcPacket, TmPacket, DeviceId, Timestamp, Payload};
+    ///
+    /// let first = Packet::TmPacket(TmPacket::new(DeviceId::Gps, Timestamp::new(10)?, Payload::from_u8(7)));
+    /// let second = Packet::TcPacket(TcPacket::new(DeviceId::System, Timestamp::new(20)?, Payload::new()));
+    /// let mut buf = [0u8; 64];
+    /// let first_len = first.encode_raw(&mut buf)?.len();
+    /// let second_len = second.encode_raw(&mut buf[first_len..])?.len();
+    /// let stream = &buf[..first_len + second_len];
+    ///
+    /// let (packet, len) = Packet::decode_raw(stream)?;
+    /// assert_eq!(packet, first);
+    /// let (packet, _) = Packet::decode_raw(&stream[len..])?;
+    /// assert_eq!(packet, second);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn decode_raw(buf: &[u8]) -> Result<(Self, usize), DecodeError> {
+        Self::decode_raw_with_options(buf, &DecodeOptions::new())
+    }
 
-            out[out_idx] = Self::decode_single(&mut buf[..idx])?;
-            out_idx += 1;
+    /// Decode a packet prefixed by its length, using the given options.
+    ///
+    /// See [`Self::decode_raw`] for details. The width of the prefix is set with
+    /// [`DecodeOptions::length_prefix`].
+    pub fn decode_raw_with_options<C: Crc16Variant>(
+        buf: &[u8],
+        options: &DecodeOptions<C>,
+    ) -> Result<(Self, usize), DecodeError> {
+        let prefix_size = options.length_prefix.size();
+        let len = match (options.length_prefix, buf) {
+            (LengthPrefix::U8, [len, ..]) => *len as usize,
+            (LengthPrefix::U16, [low, high, ..]) => u16::from_le_bytes([*low, *high]) as usize,
+            _ => return Err(DecodeError::BufferTooShort(buf.len())),
+        };
+        trace!("length prefix reports a {len} byte packet");
 
-            buf = &mut buf[idx + 1..];
-        }
+        let packet = buf
+            .get(prefix_size..prefix_size + len)
+            .ok_or(DecodeError::BufferTooShort(buf.len()))?;
+        let header = Self::parse_header(packet, options)?;
 
-        Ok((buf, &mut out[..out_idx]))
+        Ok((Self::from_header(&header, packet)?, prefix_size + len))
     }
-}
 
-#[cfg(test)]
-mod test {
-    use crate::{DeviceId, Packet, VERSION};
+    /// Decode the next frame yielded by an iterator of bytes.
+    ///
+    /// Bytes are pulled from the iterator up to and including the next `0` terminator, and the
+    /// frame is gathered in `scratch` to be decoded as with [`Self::decode_single`]. This suits
+    /// byte queues, which don't offer a contiguous buffer. `scratch` should be at least
+    /// [`TmPacket::MAX_ENCODED_SIZE`] bytes long to hold any frame.
+    ///
+    /// `Ok(None)` is returned if the iterator runs out before a terminator. The bytes pulled so
+    /// far are lost in that case, so the iterator should only run out at frame boundaries.
+    ///
+    /// # Errors
+    /// If a frame doesn't fit in `scratch`, the rest of it is skipped and an error variant is
+    /// returned, so that the next call resumes at the following frame. Otherwise, the errors of
+    /// [`Self::decode_single`] are returned.
+    ///
+    /// # Example
+    /// ```
+    /// use orbipacket::{Packet, TmPacket, DeviceId, Timestamp, Payload};
+    ///
+    /// let packet = Packet::TmPacket(TmPacket::new(DeviceId::Gps, Timestamp::new(10)?, Payload::from_u8(7)));
+    /// let mut buffer = [0u8; Packet::MAX_ENCODE_BUFFER_SIZE];
+    /// let encoded = packet.encode(&mut buffer)?;
+    /// let mut bytes = encoded.iter().copied();
+    /// let mut scratch = [0u8; TmPacket::MAX_ENCODED_SIZE];
+    ///
+    /// assert_eq!(Packet::decode_from_iter(&mut bytes, &mut scratch)?, Some(packet));
+    /// assert_eq!(Packet::decode_from_iter(&mut bytes, &mut scratch)?, None);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn decode_from_iter<I: Iterator<Item = u8>>(
+        iter: &mut I,
+        scratch: &mut [u8],
+    ) -> Result<Option<Self>, DecodeError> {
+        let mut len = 0;
+        for byte in iter {
+            // Empty frames are stray delimiters, and are skipped
+            if byte == 0 && len == 0 {
+                continue;
+            }
+            if byte == 0 {
+                let frame = scratch
+                    .get_mut(..len)
+                    .ok_or(DecodeError::FrameTooLong(len))?;
+                return Self::decode_single(frame).map(Some);
+            }
 
-    #[test]
-    fn tm_packet_decode_works() {
-        let mut buf = [
-            0x05, VERSION, 0x04, 0x04, 0x0a, 0x01, 0x01, 0x01, 0x04, 0xEF, 0xCD, 0xAB, 0x03, 0x7e,
-            0x12, 0,
-        ];
+            // Bytes past the end of the scratch buffer are dropped, but still counted
+            if let Some(slot) = scratch.get_mut(len) {
+                *slot = byte;
+            }
+            len += 1;
+        }
 
-        let packet = Packet::decode_single(&mut buf).unwrap();
+        Ok(None)
+    }
 
-        let Packet::TmPacket(packet) = packet else {
-            panic!("Decoded packet is not TmPacket")
+    /// Decode the first frame in a buffer, reporting how many bytes it occupied.
+    ///
+    /// Unlike with [`Self::decode_single`], the buffer may hold more data after the first frame,
+    /// which is left untouched. The reported length includes the frame's terminating `0` byte, and
+    /// is returned even if decoding fails, so that a reader can skip exactly the corrupt frame and
+    /// carry on with the next one. Empty frames preceding the first frame are skipped, and counted
+    /// in the reported length.
+    ///
+    /// # Errors
+    /// Besides the errors documented for [`Self::decode_single`],
+    /// [`DecodeError::UnterminatedFrame`] is returned, along with a length of 0, if the buffer
+    /// doesn't contain a complete frame.
+    ///
+    /// # Examples
+    /// ```
+    /// use orbipacket::{Packet, decode::DecodeError};
+    ///
+    /// let mut buf = [
+    ///     // A corrupt frame
+    ///     0x02, 0xFF, 0,
+    ///     // A valid frame
+    ///     0x05, 1, 0x04, 0x04, 0x0a, 0x01, 0x01, 0x01, 0x04, 0xEF, 0xCD, 0xAB, 0x03, 0x7e, 0x12, 0,
+    /// ];
+    /// # #[cfg(feature = "crc-be")]
+    /// # buf.swap(16, 17);
+    ///
+    /// let (error, skipped) = Packet::decode_single_with_len(&mut buf).unwrap_err();
+    /// assert!(matches!(error, DecodeError::BufferTooShort(_)));
+    /// assert_eq!(skipped, 3);
+    ///
+    /// let (packet, len) = Packet::decode_single_with_len(&mut buf[skipped..]).map_err(|(e, _)| e)?;
+    /// assert_eq!(len, 16);
+    /// assert!(packet.is_tm_packet());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn decode_single_with_len(buf: &mut [u8]) -> Result<(Self, usize), (DecodeError, usize)> {
+        Self::decode_single_with_len_and_options(buf, &DecodeOptions::new())
+    }
+
+    /// Decode the first frame in a buffer, reporting how many bytes it occupied, using the given
+    /// options.
+    ///
+    /// See [`Self::decode_single_with_len`] for details.
+    pub fn decode_single_with_len_and_options<C: Crc16Variant>(
+        buf: &mut [u8],
+        options: &DecodeOptions<C>,
+    ) -> Result<(Self, usize), (DecodeError, usize)> {
+        // Empty frames, i.e. stray delimiters, are skipped
+        let start = buf.iter().position(|&x| x != 0).unwrap_or(buf.len());
+        let Some(idx) = buf[start..].iter().position(|&x| x == 0) else {
+            return Err((DecodeError::UnterminatedFrame(buf.len()), 0));
         };
-        assert_eq!(packet.version(), VERSION);
+        let end = start + idx;
+
+        match Self::decode_single_with_options(&mut buf[start..end], options) {
+            Ok(packet) => Ok((packet, end + 1)),
+            Err(error) => Err((error, end + 1)),
+        }
+    }
+
+    /// Decode as many packets as fit in `out` from a [`heapless::Vec`] of received bytes.
+    ///
+    /// Decoded packets are pushed to `out`, and their frames are removed from the front of `buf`,
+    /// leaving any partial trailing frame in place so that more bytes can be appended to it. The
+    /// number of decoded packets is returned.
+    ///
+    /// # Errors
+    /// If a frame can't be decoded, an error variant is returned, as documented for
+    /// [`Self::decode_single`]. The invalid frame is removed from `buf` along with the ones
+    /// preceding it, so decoding can be resumed with the next frame.
+    ///
+    /// # Example
+    /// ```
+    /// use orbipacket::Packet;
+    ///
+    /// let mut buf: heapless::Vec<u8, 64> = heapless::Vec::new();
+    /// let mut frame = [
+    ///     0x05, 1, 0x04, 0x04, 0x0a, 0x01, 0x01, 0x01, 0x04, 0xEF, 0xCD, 0xAB, 0x03, 0x7e, 0x12, 0,
+    /// ];
+    /// # #[cfg(feature = "crc-be")]
+    /// # frame.swap(13, 14);
+    /// buf.extend_from_slice(&frame).unwrap();
+    /// buf.extend_from_slice(&frame[..4]).unwrap();
+    /// let mut out: heapless::Vec<Packet, 4> = heapless::Vec::new();
+    ///
+    /// let decoded = Packet::decode_stateless_heapless(&mut buf, &mut out)?;
+    ///
+    /// assert_eq!(decoded, 1);
+    /// assert_eq!(out.len(), 1);
+    /// assert_eq!(buf, frame[..4]);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[cfg(feature = "heapless")]
+    pub fn decode_stateless_heapless<const M: usize, const N: usize>(
+        buf: &mut heapless::Vec<u8, M>,
+        out: &mut heapless::Vec<Packet, N>,
+    ) -> Result<usize, DecodeError> {
+        let mut consumed = 0;
+        let mut decoded = 0;
+        let mut result = Ok(());
+
+        while !out.is_full() {
+            match Self::decode_single_with_len(&mut buf[consumed..]) {
+                Ok((packet, len)) => {
+                    consumed += len;
+                    decoded += 1;
+                    // Pushing can't fail because out was checked not to be full
+                    let _ = out.push(packet);
+                }
+                Err((DecodeError::UnterminatedFrame(_), _)) => break,
+                Err((error, len)) => {
+                    consumed += len;
+                    result = Err(error);
+                    break;
+                }
+            }
+        }
+
+        let remaining = buf.len() - consumed;
+        buf.copy_within(consumed.., 0);
+        buf.truncate(remaining);
+
+        result.map(|()| decoded)
+    }
+
+    pub fn decode_stateless<'a, 'b>(
+        buf: &'a mut [u8],
+        out: &'b mut [Self],
+    ) -> Result<(&'a mut [u8], &'b mut [Self]), DecodeError> {
+        let (buf, out, _) = Self::decode_stateless_with_options(buf, out, &DecodeOptions::new())?;
+        Ok((buf, out))
+    }
+
+    /// Decode the complete frames in a buffer, using the given options.
+    ///
+    /// This behaves like [`Self::decode_stateless`], but also stops once the limits set with
+    /// [`DecodeOptions::max_frames`] and [`DecodeOptions::max_bytes`] are reached, which bounds
+    /// the work done per call. The returned flag is `true` if decoding stopped because of these
+    /// limits, in which case the remaining buffer may still hold complete frames.
+    ///
+    /// # Example
+    /// ```
+    /// use orbipacket::{Packet, TmPacket, DeviceId, Timestamp, Payload, decode::DecodeOptions};
+    ///
+    /// let packet = Packet::TmPacket(TmPacket::new(DeviceId::Gps, Timestamp::new(10)?, Payload::from_u8(7)));
+    /// let mut encode_buffer = [0u8; 3 * Packet::MAX_ENCODE_BUFFER_SIZE];
+    /// let len = Packet::encode_batch(&[packet; 3], &mut encode_buffer)?.len();
+    /// let mut out = [packet; 3];
+    /// let options = DecodeOptions::new().max_frames(Some(2));
+    ///
+    /// let (remaining, decoded, limited) =
+    ///     Packet::decode_stateless_with_options(&mut encode_buffer[..len], &mut out, &options)?;
+    ///
+    /// assert_eq!(decoded.len(), 2);
+    /// assert_eq!(remaining.len(), len / 3);
+    /// assert!(limited);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn decode_stateless_with_options<'a, 'b, C: Crc16Variant>(
+        mut buf: &'a mut [u8],
+        out: &'b mut [Self],
+        options: &DecodeOptions<C>,
+    ) -> Result<(&'a mut [u8], &'b mut [Self], bool), DecodeError> {
+        let mut out_idx: usize = 0;
+        let mut budget = options.max_bytes.unwrap_or(usize::MAX);
+        let max_frames = options.max_frames.unwrap_or(usize::MAX);
+        let mut limited = false;
+
+        loop {
+            // Don't even look for frames past the byte budget
+            let window = &buf[..buf.len().min(budget)];
+            let Some(idx) = window.iter().position(|&x| x == 0) else {
+                limited = window.len() < buf.len();
+                break;
+            };
+            if out_idx >= out.len() {
+                break;
+            }
+            if out_idx >= max_frames {
+                limited = true;
+                break;
+            }
+
+            // Empty frames are stray delimiters, and are skipped
+            if idx != 0 {
+                out[out_idx] = Self::decode_single_with_options(&mut buf[..idx], options)?;
+                out_idx += 1;
+            }
+
+            budget -= idx + 1;
+            buf = &mut buf[idx + 1..];
+        }
+
+        if limited {
+            debug!("stopped decoding after {out_idx} frames, as a limit was reached");
+        }
+        Ok((buf, &mut out[..out_idx], limited))
+    }
+
+    /// Decode the complete frames in a buffer, skipping invalid ones instead of failing.
+    ///
+    /// As with [`Self::decode_stateless`], frames are decoded until the buffer runs out of
+    /// complete frames or `out` is full, and the undecoded rest of the buffer is returned along
+    /// with the decoded packets. Invalid frames are dropped, and counted by kind of error in the
+    /// returned [`DecodeStats`], which makes this suitable for monitoring the quality of a link.
+    ///
+    /// # Example
+    /// ```
+    /// use orbipacket::{Packet, TmPacket, DeviceId, Timestamp, Payload};
+    ///
+    /// let packet = Packet::TmPacket(TmPacket::new(DeviceId::Gps, Timestamp::new(10)?, Payload::from_u8(7)));
+    /// let mut encode_buffer = [0u8; Packet::MAX_ENCODE_BUFFER_SIZE];
+    /// let encoded = packet.encode(&mut encode_buffer)?;
+    ///
+    /// // A corrupt frame, followed by a valid one and an incomplete one
+    /// let mut buf = [0u8; 64];
+    /// let len = encoded.len();
+    /// buf[..len].copy_from_slice(encoded);
+    /// buf[3] ^= 0x10;
+    /// buf[len..2 * len].copy_from_slice(encoded);
+    /// buf[2 * len..2 * len + 3].copy_from_slice(&encoded[..3]);
+    /// let mut out = [packet; 4];
+    ///
+    /// let (remaining, decoded, stats) = Packet::decode_stateless_lenient(&mut buf[..2 * len + 3], &mut out);
+    ///
+    /// assert_eq!(decoded, [packet]);
+    /// assert_eq!(remaining.len(), 3);
+    /// assert_eq!(stats.crc_errors, 1);
+    /// assert_eq!(stats.total_errors(), 1);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn decode_stateless_lenient<'a, 'b>(
+        buf: &'a mut [u8],
+        out: &'b mut [Self],
+    ) -> (&'a mut [u8], &'b mut [Self], DecodeStats) {
+        let mut metrics = DecodeMetrics::default();
+        let (buf, out) = Self::decode_stateless_lenient_with_metrics(buf, out, &mut metrics);
+        (buf, out, metrics.errors)
+    }
+
+    /// Decode the complete frames in a buffer, skipping invalid ones, and update long-lived
+    /// metrics.
+    ///
+    /// This behaves like [`Self::decode_stateless_lenient`], but the outcome of every frame is
+    /// added to `metrics` rather than returned, so that a caller can keep them across calls. See
+    /// [`DecodeMetrics`] for an example.
+    pub fn decode_stateless_lenient_with_metrics<'a, 'b>(
+        mut buf: &'a mut [u8],
+        out: &'b mut [Self],
+        metrics: &mut DecodeMetrics,
+    ) -> (&'a mut [u8], &'b mut [Self]) {
+        let mut out_idx: usize = 0;
+
+        while let Some(idx) = buf.iter().position(|&x| x == 0) {
+            if out_idx >= out.len() {
+                break;
+            }
+
+            // Empty frames are stray delimiters, rather than invalid frames
+            if idx == 0 {
+                metrics.bytes_processed += 1;
+                buf = &mut buf[1..];
+                continue;
+            }
+
+            let result = Self::decode_single(&mut buf[..idx]);
+            metrics.accumulate(idx + 1, &result);
+            match result {
+                Ok(packet) => {
+                    out[out_idx] = packet;
+                    out_idx += 1;
+                }
+                // The error is only used when logging is enabled
+                Err(_error) => {
+                    debug!("skipping invalid frame: {_error}");
+                }
+            }
+
+            buf = &mut buf[idx + 1..];
+        }
+
+        (buf, &mut out[..out_idx])
+    }
+}
+
+impl TryFrom<&[u8]> for Packet {
+    type Error = DecodeError;
+
+    /// Decode a frame containing a single packet, without modifying it.
+    ///
+    /// The frame is copied to a scratch buffer before decoding, so it is left intact, unlike
+    /// with [`Packet::decode_single`].
+    ///
+    /// # Errors
+    /// Besides the errors documented for [`Packet::decode_single`], an error variant is returned
+    /// if the frame is longer than [`TmPacket::MAX_ENCODED_SIZE`].
+    ///
+    /// # Example
+    /// ```
+    /// use orbipacket::{Packet, DeviceId};
+    ///
+    /// let frame = [
+    ///     0x05, 1, 0x04, 0x04, 0x0a, 0x01, 0x01, 0x01, 0x04, 0xEF, 0xCD, 0xAB, 0x03, 0x7e, 0x12, 0,
+    /// ];
+    /// # let mut frame = frame;
+    /// # #[cfg(feature = "crc-be")]
+    /// # frame.swap(13, 14);
+    ///
+    /// let packet: Packet = frame.as_slice().try_into()?;
+    ///
+    /// assert!(packet.is_tm_packet());
+    /// assert_eq!(frame[0], 0x05);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    fn try_from(frame: &[u8]) -> Result<Self, Self::Error> {
+        let mut scratch = [0u8; InternalPacket::MAX_ENCODED_SIZE];
+        let scratch = scratch
+            .get_mut(..frame.len())
+            .ok_or(DecodeError::FrameTooLong(frame.len()))?;
+        scratch.copy_from_slice(frame);
+
+        Self::decode_single(scratch)
+    }
+}
+
+/// Split a byte stream into COBS frames, without decoding them.
+///
+/// The returned iterator yields the bytes of each frame, excluding the `0` delimiter. Empty frames,
+/// caused by consecutive delimiters, are skipped. Bytes after the last delimiter form an
+/// incomplete frame, and aren't yielded; they can be retrieved with [`Frames::remainder`]. The
+/// input is never modified.
+///
+/// # Example
+/// ```
+/// use orbipacket::decode::frames;
+///
+/// let stream = [0x02, 0xAA, 0, 0x03, 0xBB, 0xCC, 0, 0x04, 0xDD];
+/// let mut frames = frames(&stream);
+///
+/// assert_eq!(frames.next(), Some(&[0x02, 0xAA][..]));
+/// assert_eq!(frames.next(), Some(&[0x03, 0xBB, 0xCC][..]));
+/// assert_eq!(frames.next(), None);
+/// assert_eq!(frames.remainder(), [0x04, 0xDD]);
+/// ```
+pub fn frames(buf: &[u8]) -> Frames<'_> {
+    Frames { buf }
+}
+
+/// Iterator over the COBS frames in a byte stream, created by [`frames`]
+#[derive(Clone, Debug)]
+pub struct Frames<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> Frames<'a> {
+    /// The bytes which haven't been yielded as part of a frame yet
+    pub fn remainder(&self) -> &'a [u8] {
+        self.buf
+    }
+}
+
+impl<'a> Iterator for Frames<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let idx = self.buf.iter().position(|&x| x == 0)?;
+            let (frame, rest) = self.buf.split_at(idx);
+            self.buf = &rest[1..];
+            if !frame.is_empty() {
+                return Some(frame);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{frames, DecodeError, DecodeMetrics, DecodeOptions, DecodeStats};
+    use crate::checksum::{CcittFalse, OpenSafetyB};
+    use crate::encode::{crc_to_bytes, EncodeError, EncodeOptions, LengthPrefix, CRC};
+    use crate::{DeviceId, Packet, Payload, ProtocolVersion, TcPacket, Timestamp, TmPacket};
+
+    const VERSION: u8 = ProtocolVersion::V1.as_u8();
+
+    #[test]
+    fn tm_packet_decode_works() {
+        let mut buf = [
+            0x05, VERSION, 0x04, 0x04, 0x0a, 0x01, 0x01, 0x01, 0x04, 0xEF, 0xCD, 0xAB, 0x03, 0x7e,
+            0x12, 0,
+        ];
+        if cfg!(feature = "crc-be") {
+            buf.swap(13, 14);
+        }
+
+        let packet = Packet::decode_single(&mut buf).unwrap();
+
+        let Packet::TmPacket(packet) = packet else {
+            panic!("Decoded packet is not TmPacket")
+        };
+        assert_eq!(packet.version(), ProtocolVersion::V1);
         assert_eq!(packet.device_id(), &DeviceId::TimeSync);
         assert_eq!(packet.timestamp().get(), 10);
         assert_eq!(packet.payload().as_bytes(), [0xEF, 0xCD, 0xAB, 0]);
@@ -157,15 +1341,1045 @@ mod test {
             0x05, VERSION, 0x04, 0x84, 0x0a, 0x01, 0x01, 0x01, 0x04, 0xEF, 0xCD, 0xAB, 0x03, 0x014,
             0x022, 0,
         ];
+        if cfg!(feature = "crc-be") {
+            buf.swap(13, 14);
+        }
 
         let packet = Packet::decode_single(&mut buf).unwrap();
 
         let Packet::TcPacket(packet) = packet else {
             panic!("Decoded packet is not TmPacket")
         };
-        assert_eq!(packet.version(), VERSION);
+        assert_eq!(packet.version(), ProtocolVersion::V1);
         assert_eq!(packet.device_id(), &DeviceId::TimeSync);
         assert_eq!(packet.timestamp().get(), 10);
         assert_eq!(packet.payload().as_bytes(), [0xEF, 0xCD, 0xAB, 0]);
     }
+
+    #[test]
+    fn sequenced_packet_decode_works() {
+        let packet = TmPacket::new(
+            DeviceId::Gps,
+            Timestamp::new(10).unwrap(),
+            Payload::from_raw_bytes([1, 2, 3]).unwrap(),
+        )
+        .with_sequence(0xBEEF);
+        let mut buf = [0u8; TmPacket::MAX_ENCODE_BUFFER_SIZE];
+        let encoded = packet.encode(&mut buf).unwrap();
+        let mut frame = [0u8; TmPacket::MAX_ENCODED_SIZE];
+        let frame = &mut frame[..encoded.len()];
+        frame.copy_from_slice(encoded);
+
+        let decoded = Packet::decode_single(frame).unwrap();
+
+        assert_eq!(decoded, Packet::TmPacket(packet));
+    }
+
+    #[test]
+    fn frames_splits_on_delimiters_and_keeps_partial_frame() {
+        let stream = [1, 2, 0, 0, 3, 0, 4];
+        let mut frames = frames(&stream);
+
+        assert_eq!(frames.next(), Some(&[1, 2][..]));
+        assert_eq!(frames.next(), Some(&[3][..]));
+        assert_eq!(frames.next(), None);
+        assert_eq!(frames.remainder(), [4]);
+    }
+
+    #[test]
+    fn frames_yields_frames_that_decode() {
+        let mut buf = [0u8; TmPacket::MAX_ENCODE_BUFFER_SIZE];
+        let packet = TmPacket::new(DeviceId::Gps, Timestamp::new(10).unwrap(), Payload::new());
+        let encoded = packet.encode(&mut buf).unwrap();
+
+        let frame = frames(encoded).next().unwrap();
+        let mut copy = [0u8; TmPacket::MAX_ENCODED_SIZE];
+        let copy = &mut copy[..frame.len()];
+        copy.copy_from_slice(frame);
+
+        assert_eq!(
+            Packet::decode_single(copy).unwrap(),
+            Packet::TmPacket(packet)
+        );
+    }
+
+    fn encoded_with_version(version: u8) -> [u8; TmPacket::MAX_ENCODED_SIZE] {
+        let packet = TmPacket::new(DeviceId::Gps, Timestamp::new(10).unwrap(), Payload::new());
+        let mut unstuffed = [0u8; TmPacket::MAX_SIZE];
+        unstuffed[..8].copy_from_slice(&[version, 0, 2 << 2, 10, 0, 0, 0, 0]);
+        let checksum = crc_to_bytes(CRC.checksum(&unstuffed[..8]));
+        unstuffed[8..10].copy_from_slice(&checksum);
+        let mut frame = [0u8; TmPacket::MAX_ENCODED_SIZE];
+        let len = cobs::encode(&unstuffed[..packet.size()], &mut frame);
+        frame[len] = 0;
+        frame
+    }
+
+    #[test]
+    fn strict_decode_rejects_newer_version() {
+        let mut frame = encoded_with_version(9);
+
+        let result = Packet::decode_single(&mut frame);
+
+        assert!(matches!(result, Err(DecodeError::NewerVersion(9))));
+    }
+
+    #[test]
+    fn strict_decode_rejects_invalid_version() {
+        let mut frame = encoded_with_version(0);
+
+        let result = Packet::decode_single(&mut frame);
+
+        assert!(matches!(result, Err(DecodeError::UnsupportedVersion(0))));
+    }
+
+    #[test]
+    fn lenient_decode_accepts_newer_version() {
+        let mut frame = encoded_with_version(9);
+        let options = DecodeOptions::new().lenient_version(true);
+
+        let packet = Packet::decode_single_with_options(&mut frame, &options).unwrap();
+
+        let Packet::TmPacket(packet) = packet else {
+            panic!("Decoded packet is not TmPacket")
+        };
+        assert_eq!(packet.version(), ProtocolVersion::Newer(9));
+        assert_eq!(packet.device_id(), &DeviceId::Gps);
+        assert_eq!(packet.timestamp().get(), 10);
+    }
+
+    #[test]
+    fn lenient_decode_still_rejects_invalid_version() {
+        let mut frame = encoded_with_version(0);
+        let options = DecodeOptions::new().lenient_version(true);
+
+        let result = Packet::decode_single_with_options(&mut frame, &options);
+
+        assert!(matches!(result, Err(DecodeError::UnsupportedVersion(0))));
+    }
+
+    #[test]
+    fn try_from_leaves_frame_intact() {
+        let frame = encoded_with_version(VERSION);
+        let copy = frame;
+
+        let packet = Packet::try_from(&frame[..]).unwrap();
+
+        assert!(packet.is_tm_packet());
+        assert_eq!(frame, copy);
+    }
+
+    #[test]
+    fn try_from_rejects_frame_too_long() {
+        let frame = [1u8; TmPacket::MAX_ENCODED_SIZE + 1];
+
+        let result = Packet::try_from(&frame[..]);
+
+        assert!(matches!(
+            result,
+            Err(DecodeError::FrameTooLong(len)) if len == frame.len()
+        ));
+    }
+
+    fn round_trip(packet: Packet) -> Packet {
+        let mut buf = [0u8; Packet::MAX_ENCODE_BUFFER_SIZE];
+        let encoded = packet.encode(&mut buf).unwrap();
+        let mut frame = [0u8; TmPacket::MAX_ENCODED_SIZE];
+        let frame = &mut frame[..encoded.len()];
+        frame.copy_from_slice(encoded);
+
+        Packet::decode_single(frame).unwrap()
+    }
+
+    #[test]
+    fn empty_payload_tm_packet_round_trips() {
+        let packet = Packet::TmPacket(TmPacket::new(
+            DeviceId::Altimeter,
+            Timestamp::new(1234).unwrap(),
+            Payload::new(),
+        ));
+
+        assert_eq!(round_trip(packet), packet);
+    }
+
+    #[test]
+    fn empty_payload_tc_packet_round_trips() {
+        let packet = Packet::TcPacket(TcPacket::new(
+            DeviceId::Altimeter,
+            Timestamp::new(1234).unwrap(),
+            Payload::from_raw_bytes([]).unwrap(),
+        ));
+
+        let decoded = round_trip(packet);
+
+        assert_eq!(decoded, packet);
+        let Packet::TcPacket(decoded) = decoded else {
+            panic!("Decoded packet is not TcPacket")
+        };
+        assert_eq!(decoded.payload().as_bytes(), [0u8; 0]);
+        assert_eq!(decoded.size(), TcPacket::OVERHEAD);
+    }
+
+    #[test]
+    fn decode_rejects_frame_shorter_than_overhead() {
+        let unstuffed = [VERSION, 0, 0, 1, 2, 3, 4, 5, 6];
+        let mut frame = [0u8; 16];
+        let len = cobs::encode(&unstuffed, &mut frame);
+
+        let result = Packet::decode_single(&mut frame[..len]);
+
+        assert!(matches!(
+            result,
+            Err(DecodeError::BufferTooShort(len)) if len == TmPacket::OVERHEAD - 1
+        ));
+    }
+
+    #[cfg(feature = "arbitrary")]
+    proptest::proptest! {
+        #[test]
+        fn arbitrary_packet_round_trips_and_corruption_never_panics(
+            data in proptest::collection::vec(proptest::prelude::any::<u8>(), 0..1024),
+            flip in proptest::prelude::any::<usize>(),
+        ) {
+            let mut u = arbitrary::Unstructured::new(&data);
+            let packet: Packet = arbitrary::Arbitrary::arbitrary(&mut u).unwrap();
+
+            let mut buf = [0u8; Packet::MAX_ENCODE_BUFFER_SIZE];
+            let encoded = packet.encode(&mut buf).unwrap();
+            let mut frame = [0u8; TmPacket::MAX_ENCODED_SIZE];
+            let frame = &mut frame[..encoded.len()];
+            frame.copy_from_slice(encoded);
+
+            proptest::prop_assert_eq!(Packet::try_from(&*frame).unwrap(), packet);
+
+            // Corrupting the frame must result in an error or a packet, but never a panic
+            frame[flip % frame.len()] ^= 0x55;
+            let _ = Packet::decode_single(frame);
+        }
+    }
+
+    #[test]
+    fn decode_rejects_frame_longer_than_any_packet() {
+        let unstuffed = [1u8; TmPacket::MAX_SIZE + 1];
+        let mut frame = [0u8; TmPacket::MAX_ENCODED_SIZE + 2];
+        let len = cobs::encode(&unstuffed, &mut frame);
+
+        let result = Packet::decode_single(&mut frame[..len]);
+
+        assert!(matches!(
+            result,
+            Err(DecodeError::FrameTooLong(len)) if len == TmPacket::MAX_SIZE + 1
+        ));
+    }
+
+    #[test]
+    fn decode_truncated_frames_never_panics() {
+        let packet = Packet::TmPacket(
+            TmPacket::new(
+                DeviceId::Gps,
+                Timestamp::new(10).unwrap(),
+                Payload::from_raw_bytes([0xAB; 40]).unwrap(),
+            )
+            .with_sequence(7),
+        );
+        let mut buf = [0u8; Packet::MAX_ENCODE_BUFFER_SIZE];
+        let encoded = packet.encode(&mut buf).unwrap();
+
+        for end in 0..encoded.len() - 1 {
+            let mut frame = [0u8; TmPacket::MAX_ENCODED_SIZE];
+            frame[..end].copy_from_slice(&encoded[..end]);
+
+            assert!(Packet::decode_single(&mut frame[..end]).is_err());
+
+            frame[..end].copy_from_slice(&encoded[..end]);
+            // Without a terminator, the truncated frame is left for a later call
+            let mut out = [packet; 1];
+            let (remaining, decoded) =
+                Packet::decode_stateless(&mut frame[..end], &mut out).unwrap();
+            assert_eq!(remaining.len(), end);
+            assert!(decoded.is_empty());
+        }
+    }
+
+    /// Generate any valid packet, of either kind and version
+    fn packet_strategy() -> impl proptest::strategy::Strategy<Value = Packet> {
+        use proptest::prelude::*;
+
+        (
+            any::<bool>(),
+            0u8..16,
+            any::<Option<u16>>(),
+            0u64..1 << 40,
+            proptest::collection::vec(any::<u8>(), 0..=Payload::MAX_SIZE),
+        )
+            .prop_map(|(is_tm, id, sequence, timestamp, payload)| {
+                let device_id = DeviceId::try_from(id).unwrap();
+                let timestamp = Timestamp::new(timestamp).unwrap();
+                let payload = Payload::from_raw_bytes(payload).unwrap();
+                match (is_tm, sequence) {
+                    (true, None) => Packet::TmPacket(TmPacket::new(device_id, timestamp, payload)),
+                    (true, Some(sequence)) => Packet::TmPacket(
+                        TmPacket::new(device_id, timestamp, payload).with_sequence(sequence),
+                    ),
+                    (false, None) => Packet::TcPacket(TcPacket::new(device_id, timestamp, payload)),
+                    (false, Some(sequence)) => Packet::TcPacket(
+                        TcPacket::new(device_id, timestamp, payload).with_sequence(sequence),
+                    ),
+                }
+            })
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn decode_inverts_encode(packet in packet_strategy()) {
+            let mut buf = [0u8; Packet::MAX_ENCODE_BUFFER_SIZE];
+            let encoded = packet.encode(&mut buf).unwrap();
+            let mut frame = [0u8; TmPacket::MAX_ENCODED_SIZE];
+            let frame = &mut frame[..encoded.len()];
+            frame.copy_from_slice(encoded);
+
+            proptest::prop_assert_eq!(Packet::decode_single(frame).unwrap(), packet);
+        }
+
+        #[test]
+        fn encode_inverts_decode(packet in packet_strategy()) {
+            let mut buf = [0u8; Packet::MAX_ENCODE_BUFFER_SIZE];
+            let encoded = packet.encode(&mut buf).unwrap();
+            let mut frame = [0u8; TmPacket::MAX_ENCODED_SIZE];
+            let frame = &mut frame[..encoded.len()];
+            frame.copy_from_slice(encoded);
+
+            let mut reencode_buf = [0u8; Packet::MAX_ENCODE_BUFFER_SIZE];
+            let reencoded = Packet::decode_single(frame)
+                .unwrap()
+                .encode(&mut reencode_buf)
+                .unwrap();
+
+            proptest::prop_assert_eq!(reencoded, encoded);
+        }
+
+        #[test]
+        fn decode_arbitrary_bytes_never_panics(
+            mut bytes in proptest::collection::vec(proptest::prelude::any::<u8>(), 0..600),
+        ) {
+            let _ = Packet::decode_single(&mut bytes.clone());
+            let _ = Packet::decode_single_with_options(
+                &mut bytes.clone(),
+                &DecodeOptions::new().lenient_version(true).trusted_link(true),
+            );
+            let mut out = [Packet::TmPacket(TmPacket::new(DeviceId::System, Timestamp(0), Payload::new())); 4];
+            let _ = Packet::decode_stateless(&mut bytes, &mut out);
+        }
+    }
+
+    #[test]
+    fn decode_stateless_stops_when_output_is_full() {
+        let packet = Packet::TmPacket(TmPacket::new(
+            DeviceId::Gps,
+            Timestamp::new(10).unwrap(),
+            Payload::from_u8(1),
+        ));
+        let mut buf = [0u8; 3 * Packet::MAX_ENCODE_BUFFER_SIZE];
+        let mut encode_buffer = [0u8; Packet::MAX_ENCODE_BUFFER_SIZE];
+        let frames = Packet::encode_batch(&[packet, packet, packet], &mut buf)
+            .unwrap()
+            .len();
+        let frame_len = packet.encode(&mut encode_buffer).unwrap().len();
+
+        let mut out: [Packet; 0] = [];
+        let (remaining, decoded) = Packet::decode_stateless(&mut buf[..frames], &mut out).unwrap();
+        assert_eq!(remaining.len(), frames);
+        assert!(decoded.is_empty());
+
+        let mut out = [packet; 2];
+        let (remaining, decoded) = Packet::decode_stateless(&mut buf[..frames], &mut out).unwrap();
+        assert_eq!(remaining.len(), frame_len);
+        assert_eq!(decoded, [packet, packet]);
+    }
+
+    #[test]
+    fn decode_rejects_oversized_length_byte() {
+        let mut unstuffed = [VERSION, 0xFF, 0, 1, 2, 3, 4, 5, 0xAA, 0, 0];
+        let checksum = crc_to_bytes(CRC.checksum(&unstuffed[..9]));
+        unstuffed[9..].copy_from_slice(&checksum);
+        let mut frame = [0u8; 16];
+        let len = cobs::encode(&unstuffed, &mut frame);
+
+        let result = Packet::decode_single(&mut frame[..len]);
+
+        assert!(matches!(
+            result,
+            Err(DecodeError::InvalidLength {
+                expected: 1,
+                found: 0xFF
+            })
+        ));
+    }
+
+    fn encoded_with_control(control: u8) -> [u8; 16] {
+        let mut unstuffed = [VERSION, 0, control, 10, 0, 0, 0, 0, 0, 0];
+        let checksum = crc_to_bytes(CRC.checksum(&unstuffed[..8]));
+        unstuffed[8..].copy_from_slice(&checksum);
+        let mut frame = [0u8; 16];
+        cobs::encode(&unstuffed, &mut frame);
+        frame
+    }
+
+    #[test]
+    fn decode_stateless_lenient_counts_errors_by_kind() {
+        let packet = Packet::TmPacket(TmPacket::new(
+            DeviceId::Gps,
+            Timestamp::new(10).unwrap(),
+            Payload::from_u8(1),
+        ));
+        let mut encode_buffer = [0u8; Packet::MAX_ENCODE_BUFFER_SIZE];
+        let mut buf = Vec::new();
+        // Invalid COBS, as the code byte announces more bytes than the frame holds
+        buf.extend_from_slice(&[0x05, 1, 0]);
+        // Too short to hold a header
+        buf.extend_from_slice(&[0x04, 1, 1, 1, 0]);
+        // Unsupported version
+        let mut unstuffed = [9, 0, 2 << 2, 10, 0, 0, 0, 0, 0, 0];
+        let checksum = crc_to_bytes(CRC.checksum(&unstuffed[..8]));
+        unstuffed[8..].copy_from_slice(&checksum);
+        let mut frame = [0u8; 16];
+        let len = cobs::encode(&unstuffed, &mut frame);
+        buf.extend_from_slice(&frame[..len + 1]);
+        // Valid packet
+        buf.extend_from_slice(packet.encode(&mut encode_buffer).unwrap());
+        let mut out = [packet; 4];
+
+        let (remaining, decoded, stats) = Packet::decode_stateless_lenient(&mut buf, &mut out);
+
+        assert!(remaining.is_empty());
+        assert_eq!(decoded, [packet]);
+        assert_eq!(
+            stats,
+            DecodeStats {
+                cobs_errors: 1,
+                length_errors: 1,
+                crc_errors: 0,
+                version_errors: 1,
+                other_errors: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn decode_stateless_honors_byte_budget() {
+        let packet = Packet::TmPacket(TmPacket::new(
+            DeviceId::Gps,
+            Timestamp::new(10).unwrap(),
+            Payload::from_u8(1),
+        ));
+        let mut buf = [0u8; 2 * Packet::MAX_ENCODE_BUFFER_SIZE];
+        let len = Packet::encode_batch(&[packet, packet], &mut buf)
+            .unwrap()
+            .len();
+        let frame_len = len / 2;
+        let mut out = [packet; 2];
+
+        // The second frame doesn't fit in the budget by a single byte
+        let options = DecodeOptions::new().max_bytes(Some(len - 1));
+        let (remaining, decoded, limited) =
+            Packet::decode_stateless_with_options(&mut buf[..len].to_vec(), &mut out, &options)
+                .map(|(remaining, decoded, limited)| (remaining.len(), decoded.len(), limited))
+                .unwrap();
+        assert_eq!((remaining, decoded, limited), (frame_len, 1, true));
+
+        // Both frames fit exactly in the budget
+        let options = DecodeOptions::new().max_bytes(Some(len));
+        let (remaining, decoded, limited) =
+            Packet::decode_stateless_with_options(&mut buf[..len], &mut out, &options).unwrap();
+        assert!(remaining.is_empty());
+        assert_eq!(decoded, [packet, packet]);
+        assert!(!limited);
+    }
+
+    #[test]
+    fn is_valid_frame_agrees_with_decode() {
+        let packet = Packet::TcPacket(
+            TcPacket::new(
+                DeviceId::Camera,
+                Timestamp::new(1234).unwrap(),
+                Payload::from_raw_bytes([1, 0, 2]).unwrap(),
+            )
+            .with_sequence(42),
+        );
+        let mut buffer = [0u8; Packet::MAX_ENCODE_BUFFER_SIZE];
+        let encoded = packet.encode(&mut buffer).unwrap();
+
+        for idx in 0..encoded.len() {
+            let mut frame = [0u8; TmPacket::MAX_ENCODED_SIZE];
+            let frame = &mut frame[..encoded.len()];
+            frame.copy_from_slice(encoded);
+            frame[idx] ^= 0x20;
+            let original = Vec::from(&*frame);
+
+            let valid = Packet::is_valid_frame_copied(frame);
+
+            assert_eq!(frame, &original[..]);
+            assert_eq!(valid, Packet::decode_single(frame).is_ok());
+        }
+        assert!(Packet::is_valid_frame_copied(encoded));
+        assert!(!Packet::is_valid_frame_copied(
+            &[1; TmPacket::MAX_ENCODED_SIZE + 1]
+        ));
+    }
+
+    #[test]
+    fn decode_from_iter_skips_frame_too_long_for_scratch() {
+        let packet = Packet::TmPacket(TmPacket::new(
+            DeviceId::Gps,
+            Timestamp::new(10).unwrap(),
+            Payload::from_raw_bytes([0xAB; 20]).unwrap(),
+        ));
+        let short = Packet::TcPacket(TcPacket::new(
+            DeviceId::System,
+            Timestamp::new(0).unwrap(),
+            Payload::new(),
+        ));
+        let mut buf = [0u8; 2 * Packet::MAX_ENCODE_BUFFER_SIZE];
+        let encoded = Packet::encode_batch(&[packet, short], &mut buf).unwrap();
+        let mut bytes = encoded.iter().copied();
+        let mut scratch = [0u8; 16];
+
+        assert!(matches!(
+            Packet::decode_from_iter(&mut bytes, &mut scratch),
+            Err(DecodeError::FrameTooLong(len)) if len == packet.encoded_size() - 1
+        ));
+        assert_eq!(
+            Packet::decode_from_iter(&mut bytes, &mut scratch).unwrap(),
+            Some(short)
+        );
+        assert_eq!(
+            Packet::decode_from_iter(&mut bytes, &mut scratch).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn streaming_decoders_skip_empty_frames() {
+        let packet = Packet::TmPacket(TmPacket::new(
+            DeviceId::Gps,
+            Timestamp::new(10).unwrap(),
+            Payload::from_u8(1),
+        ));
+        let mut encode_buffer = [0u8; Packet::MAX_ENCODE_BUFFER_SIZE];
+        let frame = packet.encode(&mut encode_buffer).unwrap();
+        let mut stream = Vec::new();
+        stream.extend_from_slice(frame);
+        stream.extend_from_slice(&[0, 0]);
+        stream.extend_from_slice(frame);
+
+        let mut out = [packet; 4];
+        let mut buf = stream.clone();
+        let (remaining, decoded) = Packet::decode_stateless(&mut buf, &mut out).unwrap();
+        assert!(remaining.is_empty());
+        assert_eq!(decoded, [packet, packet]);
+
+        let mut buf = stream.clone();
+        let (remaining, decoded, stats) = Packet::decode_stateless_lenient(&mut buf, &mut out);
+        assert!(remaining.is_empty());
+        assert_eq!(decoded, [packet, packet]);
+        assert_eq!(stats.total_errors(), 0);
+
+        let mut bytes = stream.iter().copied();
+        let mut scratch = [0u8; TmPacket::MAX_ENCODED_SIZE];
+        assert_eq!(
+            Packet::decode_from_iter(&mut bytes, &mut scratch).unwrap(),
+            Some(packet)
+        );
+        assert_eq!(
+            Packet::decode_from_iter(&mut bytes, &mut scratch).unwrap(),
+            Some(packet)
+        );
+
+        let mut buf = stream.clone();
+        let (first, len) = Packet::decode_single_with_len(&mut buf).unwrap();
+        let (second, second_len) = Packet::decode_single_with_len(&mut buf[len..]).unwrap();
+        assert_eq!([first, second], [packet, packet]);
+        assert_eq!(len + second_len, stream.len());
+
+        assert_eq!(frames(&stream).count(), 2);
+    }
+
+    #[test]
+    fn control_flags_round_trip() {
+        let mut frame = [
+            0x02, 0x01, 0x03, 0x09, 0x0a, 0x01, 0x01, 0x01, 0x03, 0x9e, 0x81, 0x00,
+        ];
+        if cfg!(feature = "crc-be") {
+            frame.swap(9, 10);
+        }
+        let original = frame;
+        let options = DecodeOptions::new().allow_reserved_bits(true);
+
+        let packet = Packet::decode_single_with_options(&mut frame, &options).unwrap();
+        assert_eq!(packet.control_flags(), 0b01);
+
+        let mut buffer = [0u8; Packet::MAX_ENCODE_BUFFER_SIZE];
+        assert_eq!(packet.encode(&mut buffer).unwrap(), original);
+    }
+
+    #[test]
+    fn decode_metrics_persist_across_calls() {
+        let packet = Packet::TmPacket(TmPacket::new(
+            DeviceId::Gps,
+            Timestamp::new(10).unwrap(),
+            Payload::from_u8(1),
+        ));
+        let mut encode_buffer = [0u8; Packet::MAX_ENCODE_BUFFER_SIZE];
+        let frame = packet.encode(&mut encode_buffer).unwrap().to_vec();
+        let mut metrics = DecodeMetrics::default();
+        let mut out = [packet; 4];
+
+        let mut buf = [&frame[..], &[0], &frame[..]].concat();
+        Packet::decode_stateless_lenient_with_metrics(&mut buf, &mut out, &mut metrics);
+        let mut buf = frame.clone();
+        buf[4] ^= 0x01;
+        Packet::decode_stateless_lenient_with_metrics(&mut buf, &mut out, &mut metrics);
+
+        assert_eq!(
+            metrics,
+            DecodeMetrics {
+                frames_seen: 3,
+                frames_decoded: 2,
+                bytes_processed: 3 * frame.len() as u64 + 1,
+                errors: DecodeStats {
+                    crc_errors: 1,
+                    ..DecodeStats::default()
+                },
+            }
+        );
+        assert_eq!(metrics.frame_error_rate(), 1.0 / 3.0);
+        assert_eq!(DecodeMetrics::default().frame_error_rate(), 0.0);
+    }
+
+    #[test]
+    fn decode_stats_accumulate() {
+        let mut stats = DecodeStats {
+            crc_errors: 1,
+            ..Default::default()
+        };
+
+        stats += DecodeStats {
+            crc_errors: 2,
+            other_errors: 1,
+            ..Default::default()
+        };
+
+        assert_eq!(stats.crc_errors, 3);
+        assert_eq!(stats.total_errors(), 4);
+    }
+
+    #[test]
+    fn peek_header_matches_decoded_packet() {
+        let packet = TcPacket::new(
+            DeviceId::Camera,
+            Timestamp::new(1234).unwrap(),
+            Payload::from_raw_bytes([1, 0, 2]).unwrap(),
+        )
+        .with_sequence(42);
+        let mut buffer = [0u8; TcPacket::MAX_ENCODE_BUFFER_SIZE];
+        let mut frame = [0u8; TcPacket::MAX_ENCODED_SIZE];
+        let encoded = packet.encode(&mut buffer).unwrap();
+        let len = encoded.len();
+        frame[..len].copy_from_slice(encoded);
+
+        let header = Packet::peek_header(&mut frame[..len]).unwrap();
+
+        assert_eq!(header.version(), packet.version());
+        assert_eq!(header.device_id(), packet.device_id());
+        assert!(header.is_tc_packet());
+        assert_eq!(header.sequence(), Some(42));
+        assert_eq!(header.timestamp(), packet.timestamp());
+        assert_eq!(header.payload_length(), 3);
+        assert_eq!(frame[header.payload_range()], [1, 0, 2]);
+    }
+
+    #[test]
+    fn peek_header_validates_checksum() {
+        let mut unstuffed = [VERSION, 0, 2 << 2, 10, 0, 0, 0, 0, 0, 0];
+        let checksum = crc_to_bytes(CRC.checksum(&unstuffed[..8]) ^ 1);
+        unstuffed[8..].copy_from_slice(&checksum);
+        let mut frame = [0u8; 16];
+        cobs::encode(&unstuffed, &mut frame);
+
+        let result = Packet::peek_header(&mut frame);
+
+        assert!(matches!(result, Err(DecodeError::InvalidChecksum { .. })));
+    }
+
+    #[test]
+    fn decode_single_with_len_reports_unterminated_frame() {
+        let mut buf = [0x05, VERSION, 0x04, 0x04];
+
+        let result = Packet::decode_single_with_len(&mut buf);
+
+        assert!(matches!(
+            result,
+            Err((DecodeError::UnterminatedFrame(4), 0))
+        ));
+    }
+
+    #[test]
+    fn decode_single_with_len_skips_corrupt_frames() {
+        let packet = Packet::TmPacket(TmPacket::new(
+            DeviceId::Gps,
+            Timestamp::new(10).unwrap(),
+            Payload::from_raw_bytes([1, 2, 3]).unwrap(),
+        ));
+        let mut buffer = [0u8; Packet::MAX_ENCODE_BUFFER_SIZE];
+        let encoded = packet.encode(&mut buffer).unwrap();
+        let mut stream = [0u8; 2 * TmPacket::MAX_ENCODED_SIZE];
+        let mut len = 0;
+        for corrupt in [false, true, false] {
+            stream[len..len + encoded.len()].copy_from_slice(encoded);
+            if corrupt {
+                // Corrupt the timestamp, which invalidates the CRC
+                stream[len + 5] ^= 0x10;
+            }
+            len += encoded.len();
+        }
+
+        let mut idx = 0;
+        let mut results = [None, None, None];
+        for result in &mut results {
+            let (decoded, frame_len) = match Packet::decode_single_with_len(&mut stream[idx..len]) {
+                Ok((packet, frame_len)) => (Some(packet), frame_len),
+                Err((_, frame_len)) => (None, frame_len),
+            };
+            *result = decoded;
+            idx += frame_len;
+        }
+
+        assert_eq!(idx, len);
+        assert_eq!(results, [Some(packet), None, Some(packet)]);
+    }
+
+    #[cfg(feature = "heapless")]
+    #[test]
+    fn decode_stateless_heapless_stops_when_out_is_full() {
+        let packet = Packet::TmPacket(TmPacket::new(
+            DeviceId::Gps,
+            Timestamp::new(10).unwrap(),
+            Payload::from_raw_bytes([1, 2, 3]).unwrap(),
+        ));
+        let mut buffer = [0u8; Packet::MAX_ENCODE_BUFFER_SIZE];
+        let encoded = packet.encode(&mut buffer).unwrap();
+        let mut buf: heapless::Vec<u8, 64> = heapless::Vec::new();
+        for _ in 0..3 {
+            buf.extend_from_slice(encoded).unwrap();
+        }
+        let mut out: heapless::Vec<Packet, 2> = heapless::Vec::new();
+
+        assert_eq!(
+            Packet::decode_stateless_heapless(&mut buf, &mut out).unwrap(),
+            2
+        );
+        assert_eq!(out, [packet, packet]);
+        assert_eq!(buf, encoded);
+
+        out.clear();
+        assert_eq!(
+            Packet::decode_stateless_heapless(&mut buf, &mut out).unwrap(),
+            1
+        );
+        assert!(buf.is_empty());
+    }
+
+    #[cfg(feature = "heapless")]
+    #[test]
+    fn decode_stateless_heapless_drains_invalid_frame() {
+        let mut buf: heapless::Vec<u8, 16> = heapless::Vec::new();
+        buf.extend_from_slice(&[0x02, 0xFF, 0, 0x02]).unwrap();
+        let mut out: heapless::Vec<Packet, 2> = heapless::Vec::new();
+
+        let result = Packet::decode_stateless_heapless(&mut buf, &mut out);
+
+        assert!(matches!(result, Err(DecodeError::BufferTooShort(_))));
+        assert!(out.is_empty());
+        assert_eq!(buf, [0x02]);
+    }
+
+    #[test]
+    fn decode_rejects_length_byte_larger_than_frame() {
+        let mut unstuffed = [VERSION, 0xFF, 2 << 2, 10, 0, 0, 0, 0, 0xAB, 0, 0];
+        let checksum = crc_to_bytes(CRC.checksum(&unstuffed[..9]));
+        unstuffed[9..].copy_from_slice(&checksum);
+        let mut frame = [0u8; 16];
+        let len = cobs::encode(&unstuffed, &mut frame);
+
+        let result = Packet::decode_single(&mut frame[..len + 1]);
+
+        assert!(matches!(
+            result,
+            Err(DecodeError::InvalidLength {
+                expected: 1,
+                found: 0xFF
+            })
+        ));
+    }
+
+    #[test]
+    fn decode_rejects_reserved_bits_by_default() {
+        let mut frame = encoded_with_control(2 << 2 | 0b10);
+
+        let result = Packet::decode_single(&mut frame);
+
+        assert!(matches!(result, Err(DecodeError::ReservedBitsSet(0b10))));
+    }
+
+    #[test]
+    fn decode_allows_reserved_bits_when_configured() {
+        let mut frame = encoded_with_control(2 << 2 | 0b01);
+        let options = DecodeOptions::new().allow_reserved_bits(true);
+
+        let packet = Packet::decode_single_with_options(&mut frame, &options).unwrap();
+
+        let Packet::TmPacket(packet) = packet else {
+            panic!("Decoded packet is not TmPacket")
+        };
+        assert_eq!(packet.device_id(), &DeviceId::Gps);
+    }
+
+    #[test]
+    fn decode_rejects_devices_not_allowed() {
+        let options =
+            DecodeOptions::new().allowed_devices(&[DeviceId::System, DeviceId::Altimeter]);
+        let mut frame = encoded_with_control(2 << 2);
+
+        let result = Packet::decode_single_with_options(&mut frame, &options);
+
+        assert!(matches!(
+            result,
+            Err(DecodeError::UnexpectedDevice(DeviceId::Gps))
+        ));
+    }
+
+    #[test]
+    fn decode_accepts_allowed_devices() {
+        let options = DecodeOptions::new().allowed_devices(&[DeviceId::Gps]);
+        let mut frame = encoded_with_control(2 << 2);
+
+        let packet = Packet::decode_single_with_options(&mut frame, &options).unwrap();
+
+        let Packet::TmPacket(packet) = packet else {
+            panic!("Decoded packet is not TmPacket")
+        };
+        assert_eq!(packet.device_id(), &DeviceId::Gps);
+    }
+
+    #[test]
+    fn decode_raw_round_trip_with_options() {
+        let packet = Packet::TcPacket(
+            TcPacket::new(
+                DeviceId::Gps,
+                Timestamp::new(10).unwrap(),
+                Payload::from_raw_bytes([0xEF, 0xCD, 0xAB]).unwrap(),
+            )
+            .with_sequence(7),
+        );
+        let encode_options = EncodeOptions::new()
+            .trusted_link(true)
+            .length_prefix(LengthPrefix::U8);
+        let decode_options = DecodeOptions::new()
+            .trusted_link(true)
+            .length_prefix(LengthPrefix::U8);
+        let mut buffer = [0u8; 1 + TmPacket::MAX_SIZE];
+
+        let framed = packet
+            .encode_raw_with_options(&mut buffer, &encode_options)
+            .unwrap();
+
+        assert_eq!(framed[0] as usize, packet.size() - TmPacket::CRC_SIZE);
+        assert_eq!(
+            Packet::decode_raw_with_options(framed, &decode_options).unwrap(),
+            (packet, framed.len())
+        );
+    }
+
+    #[test]
+    fn encode_raw_rejects_packet_too_long_for_prefix() {
+        let packet = Packet::TmPacket(TmPacket::new(
+            DeviceId::Gps,
+            Timestamp::new(10).unwrap(),
+            Payload::from_raw_bytes([0xAA; Payload::MAX_SIZE]).unwrap(),
+        ));
+        let options = EncodeOptions::new().length_prefix(LengthPrefix::U8);
+        let mut buffer = [0u8; 1 + TmPacket::MAX_SIZE];
+
+        let result = packet.encode_raw_with_options(&mut buffer, &options);
+
+        assert!(matches!(
+            result,
+            Err(EncodeError::FrameTooLong(len)) if len == packet.size()
+        ));
+    }
+
+    #[test]
+    fn decode_raw_rejects_truncated_packet() {
+        let packet = Packet::TmPacket(TmPacket::new(
+            DeviceId::Gps,
+            Timestamp::new(10).unwrap(),
+            Payload::from_u8(1),
+        ));
+        let mut buffer = [0u8; 2 + TmPacket::MAX_SIZE];
+        let framed = packet.encode_raw(&mut buffer).unwrap();
+
+        for end in 0..framed.len() {
+            assert!(matches!(
+                Packet::decode_raw(&framed[..end]),
+                Err(DecodeError::BufferTooShort(len)) if len == end
+            ));
+        }
+    }
+
+    #[test]
+    fn decode_rejects_suspicious_payload_length() {
+        let options = DecodeOptions::new()
+            .expected_payload_len(DeviceId::Gps, 1..=4)
+            .expected_payload_len(DeviceId::System, 0..=0);
+        let mut frame = encoded_with_control(2 << 2);
+
+        let result = Packet::decode_single_with_options(&mut frame, &options);
+
+        assert!(matches!(
+            result,
+            Err(DecodeError::SuspiciousLength {
+                device: DeviceId::Gps,
+                length: 0
+            })
+        ));
+    }
+
+    #[test]
+    fn decode_accepts_expected_payload_length() {
+        let options = DecodeOptions::new().expected_payload_len(DeviceId::Gps, 0..=300);
+        let mut frame = encoded_with_control(2 << 2);
+
+        assert!(Packet::decode_single_with_options(&mut frame, &options).is_ok());
+    }
+
+    #[test]
+    fn decode_trusted_link_round_trip() {
+        let packets = [
+            Packet::TmPacket(TmPacket::new(
+                DeviceId::Gps,
+                Timestamp::new(10).unwrap(),
+                Payload::from_raw_bytes([0xEF, 0xCD, 0xAB]).unwrap(),
+            )),
+            Packet::TcPacket(
+                TcPacket::new(DeviceId::System, Timestamp::new(0).unwrap(), Payload::new())
+                    .with_sequence(7),
+            ),
+        ];
+        let encode_options = EncodeOptions::new().trusted_link(true);
+        let decode_options = DecodeOptions::new().trusted_link(true);
+
+        for packet in packets {
+            let mut buffer = [0u8; Packet::MAX_ENCODE_BUFFER_SIZE];
+            let mut frame = [0u8; TmPacket::MAX_ENCODED_SIZE];
+            let encoded = packet
+                .encode_with_options(&mut buffer, &encode_options)
+                .unwrap();
+            assert_eq!(encoded.len(), packet.encoded_size() - TmPacket::CRC_SIZE);
+            frame[..encoded.len()].copy_from_slice(encoded);
+
+            let decoded =
+                Packet::decode_single_with_options(&mut frame[..encoded.len()], &decode_options);
+
+            assert_eq!(decoded.unwrap(), packet);
+        }
+    }
+
+    #[test]
+    fn crc_variants_produce_different_checksums_and_round_trip() {
+        let packet = Packet::TmPacket(TmPacket::new(
+            DeviceId::Gps,
+            Timestamp::new(10).unwrap(),
+            Payload::from_raw_bytes([0xEF, 0xCD, 0xAB]).unwrap(),
+        ));
+        let mut default_buffer = [0u8; Packet::MAX_ENCODE_BUFFER_SIZE];
+        let mut ccitt_buffer = [0u8; Packet::MAX_ENCODE_BUFFER_SIZE];
+
+        let default = packet
+            .encode_with_options(&mut default_buffer, &EncodeOptions::new())
+            .unwrap();
+        let ccitt = packet
+            .encode_with_options(
+                &mut ccitt_buffer,
+                &EncodeOptions::new().crc_variant::<CcittFalse>(),
+            )
+            .unwrap();
+
+        assert_eq!(default.len(), ccitt.len());
+        let crc_bytes = default.len() - 3..default.len() - 1;
+        assert_eq!(default[..crc_bytes.start], ccitt[..crc_bytes.start]);
+        assert_ne!(default[crc_bytes.clone()], ccitt[crc_bytes]);
+
+        let mut frame = [0u8; TmPacket::MAX_ENCODED_SIZE];
+        let len = default.len();
+        frame[..len].copy_from_slice(default);
+        let options = DecodeOptions::new().crc_variant::<OpenSafetyB>();
+        assert_eq!(
+            Packet::decode_single_with_options(&mut frame[..len], &options).unwrap(),
+            packet
+        );
+
+        frame[..len].copy_from_slice(ccitt);
+        let options = DecodeOptions::new().crc_variant::<CcittFalse>();
+        assert_eq!(
+            Packet::decode_single_with_options(&mut frame[..len], &options).unwrap(),
+            packet
+        );
+
+        frame[..len].copy_from_slice(ccitt);
+        assert!(matches!(
+            Packet::decode_single(&mut frame[..len]),
+            Err(DecodeError::InvalidChecksum { .. })
+        ));
+    }
+
+    #[test]
+    fn decode_trusted_link_rejects_frame_with_checksum() {
+        let packet = Packet::TmPacket(TmPacket::new(
+            DeviceId::Gps,
+            Timestamp::new(10).unwrap(),
+            Payload::from_raw_bytes([0xEF, 0xCD, 0xAB]).unwrap(),
+        ));
+        let mut buffer = [0u8; Packet::MAX_ENCODE_BUFFER_SIZE];
+        let mut frame = [0u8; TmPacket::MAX_ENCODED_SIZE];
+        let encoded = packet.encode(&mut buffer).unwrap();
+        let len = encoded.len();
+        frame[..len].copy_from_slice(encoded);
+
+        let result = Packet::decode_single_with_options(
+            &mut frame[..len],
+            &DecodeOptions::new().trusted_link(true),
+        );
+
+        assert!(matches!(result, Err(DecodeError::UnexpectedChecksum)));
+    }
+
+    #[test]
+    fn decode_rejects_trusted_link_frame_by_default() {
+        let packet = Packet::TmPacket(TmPacket::new(
+            DeviceId::Gps,
+            Timestamp::new(10).unwrap(),
+            Payload::from_raw_bytes([0xEF, 0xCD, 0xAB]).unwrap(),
+        ));
+        let mut buffer = [0u8; Packet::MAX_ENCODE_BUFFER_SIZE];
+        let mut frame = [0u8; TmPacket::MAX_ENCODED_SIZE];
+        let encoded = packet
+            .encode_with_options(&mut buffer, &EncodeOptions::new().trusted_link(true))
+            .unwrap();
+        let len = encoded.len();
+        frame[..len].copy_from_slice(encoded);
+
+        let result = Packet::decode_single(&mut frame[..len]);
+
+        assert!(matches!(result, Err(DecodeError::InvalidLength { .. })));
+    }
 }
@@ -35,25 +35,38 @@ pub enum DeviceId {
     Mission4 = 15,
 }
 
+impl DeviceId {
+    /// The numeric ID of the device, as written in the packet's control byte
+    ///
+    /// # Example
+    /// ```
+    /// # use orbipacket::DeviceId;
+    /// assert_eq!(DeviceId::Gps.as_u8(), 2);
+    /// ```
+    pub const fn as_u8(&self) -> u8 {
+        *self as u8
+    }
+}
+
 impl Display for DeviceId {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
-            DeviceId::System => write!(f, "System Device (ID {})", *self as u8),
-            DeviceId::TimeSync => write!(f, "Time Sync Device (ID {})", *self as u8),
-            DeviceId::Gps => write!(f, "GPS Device (ID {})", *self as u8),
-            DeviceId::Camera => write!(f, "Camera Device (ID {})", *self as u8),
-            DeviceId::Accelerometer => write!(f, "Accelerometer Device (ID {})", *self as u8),
-            DeviceId::Gyroscope => write!(f, "Gyroscope Device (ID {})", *self as u8),
-            DeviceId::Altimeter => write!(f, "Altimeter Device (ID {})", *self as u8),
-            DeviceId::Magnetometer => write!(f, "Magnetometer Device (ID {})", *self as u8),
-            DeviceId::PressureSensor => write!(f, "Pressure Sensor Device (ID {})", *self as u8),
+            DeviceId::System => write!(f, "System Device (ID {})", self.as_u8()),
+            DeviceId::TimeSync => write!(f, "Time Sync Device (ID {})", self.as_u8()),
+            DeviceId::Gps => write!(f, "GPS Device (ID {})", self.as_u8()),
+            DeviceId::Camera => write!(f, "Camera Device (ID {})", self.as_u8()),
+            DeviceId::Accelerometer => write!(f, "Accelerometer Device (ID {})", self.as_u8()),
+            DeviceId::Gyroscope => write!(f, "Gyroscope Device (ID {})", self.as_u8()),
+            DeviceId::Altimeter => write!(f, "Altimeter Device (ID {})", self.as_u8()),
+            DeviceId::Magnetometer => write!(f, "Magnetometer Device (ID {})", self.as_u8()),
+            DeviceId::PressureSensor => write!(f, "Pressure Sensor Device (ID {})", self.as_u8()),
             DeviceId::TemperatureSensor => {
-                write!(f, "Temperature Sensor Device (ID {})", *self as u8)
+                write!(f, "Temperature Sensor Device (ID {})", self.as_u8())
             }
-            DeviceId::HumiditySensor => write!(f, "Humidity Sensor Device (ID {})", *self as u8),
-            DeviceId::RadiationSensor => write!(f, "Radiation Sensor Device (ID {})", *self as u8),
+            DeviceId::HumiditySensor => write!(f, "Humidity Sensor Device (ID {})", self.as_u8()),
+            DeviceId::RadiationSensor => write!(f, "Radiation Sensor Device (ID {})", self.as_u8()),
             DeviceId::Mission1 | DeviceId::Mission2 | DeviceId::Mission3 | DeviceId::Mission4 => {
-                write!(f, "Mission Device (ID {})", *self as u8)
+                write!(f, "Mission Device (ID {})", self.as_u8())
             }
         }
     }
@@ -84,3 +97,11 @@ impl TryFrom<u8> for DeviceId {
         }
     }
 }
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for DeviceId {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        // Unwrapping is safe here because all IDs in this range are valid
+        Ok(u.int_in_range(0..=15u8)?.try_into().unwrap())
+    }
+}
@@ -1,4 +1,7 @@
-use crate::{InternalPacket, Packet, Payload, TcPacket, TmPacket};
+use core::marker::PhantomData;
+
+use crate::checksum::{Crc16Variant, OpenSafetyB};
+use crate::{DeviceId, InternalPacket, Packet, ProtocolVersion, TcPacket, Timestamp, TmPacket};
 
 /// Error that can occur when encoding a packet
 #[derive(thiserror::Error, Debug)]
@@ -7,47 +10,260 @@ pub enum EncodeError {
     /// The provided buffer is too small to hold the encoded packet
     #[error("buffer too small: required {required} bytes, but only {available} available")]
     BufferTooSmall { required: usize, available: usize },
+    /// The packet is too long for its length to fit in the length prefix. The length of the
+    /// packet is returned as the contents of this variant.
+    #[error("packet too long for the length prefix: {0} bytes")]
+    FrameTooLong(usize),
+    /// The payload is too long for its length to fit in the packet's length field. The length of
+    /// the payload is returned as the contents of this variant.
+    #[error("payload too long: {0} bytes")]
+    PayloadTooLong(usize),
+}
+
+// The length of a payload is written to a single byte of the header
+const _: () = assert!(crate::Payload::MAX_SIZE <= u8::MAX as usize);
+
+/// Width of the length prefix of packets framed with [`Packet::encode_raw`]
+///
+/// The prefix is written in little endian, and holds the length of the packet following it, CRC
+/// included. A single byte can't hold the length of the longest packets, so encoding fails for
+/// these with [`LengthPrefix::U8`].
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum LengthPrefix {
+    /// A single byte prefix, for packets up to 255 bytes long
+    U8,
+    /// A two byte prefix, able to hold the length of any packet
+    #[default]
+    U16,
+}
+
+impl LengthPrefix {
+    /// Number of bytes taken by the prefix
+    pub const fn size(&self) -> usize {
+        match self {
+            LengthPrefix::U8 => 1,
+            LengthPrefix::U16 => 2,
+        }
+    }
+}
+
+/// Options controlling how packets are encoded
+///
+/// The default options produce packets following the protocol, protected by a CRC.
+///
+/// # Example
+/// ```
+/// # use orbipacket::encode::EncodeOptions;
+/// let options = EncodeOptions::new().trusted_link(true);
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub struct EncodeOptions<C = OpenSafetyB> {
+    trusted_link: bool,
+    length_prefix: LengthPrefix,
+    crc_variant: PhantomData<C>,
+}
+
+impl EncodeOptions {
+    /// Create the default encode options.
+    pub const fn new() -> Self {
+        EncodeOptions {
+            trusted_link: false,
+            length_prefix: LengthPrefix::U16,
+            crc_variant: PhantomData,
+        }
+    }
+}
+
+impl<C: Crc16Variant> EncodeOptions<C> {
+    /// Use the given CRC algorithm, instead of the one specified by the protocol.
+    ///
+    /// Packets must be decoded with the same variant, using
+    /// [`DecodeOptions::crc_variant`](crate::decode::DecodeOptions::crc_variant).
+    ///
+    /// # Example
+    /// ```
+    /// # use orbipacket::{checksum::CcittFalse, encode::EncodeOptions};
+    /// let options = EncodeOptions::new().crc_variant::<CcittFalse>();
+    /// ```
+    pub const fn crc_variant<V: Crc16Variant>(self) -> EncodeOptions<V> {
+        EncodeOptions {
+            trusted_link: self.trusted_link,
+            length_prefix: self.length_prefix,
+            crc_variant: PhantomData,
+        }
+    }
+
+    /// Whether to omit the CRC from encoded packets.
+    ///
+    /// This saves [`TmPacket::CRC_SIZE`] bytes per packet, and the time spent computing the
+    /// checksum, on links which are reliable enough not to need it (e.g. a wired debug bench).
+    /// Since the wire format differs, such packets must be decoded with
+    /// [`DecodeOptions::trusted_link`](crate::decode::DecodeOptions::trusted_link) enabled.
+    pub const fn trusted_link(self, trusted: bool) -> Self {
+        EncodeOptions {
+            trusted_link: trusted,
+            ..self
+        }
+    }
+
+    /// Width of the length prefix written by [`Packet::encode_raw_with_options`].
+    ///
+    /// This has no effect on COBS framed packets. Packets must be decoded with the same width,
+    /// set using [`DecodeOptions::length_prefix`](crate::decode::DecodeOptions::length_prefix).
+    pub const fn length_prefix(self, length_prefix: LengthPrefix) -> Self {
+        EncodeOptions {
+            length_prefix,
+            ..self
+        }
+    }
 }
 
+#[cfg(any(test, not(feature = "crc-constant-time")))]
 pub(crate) static CRC: crc::Crc<u16> = crc::Crc::<u16>::new(&crc::CRC_16_OPENSAFETY_B);
 
+/// Same as [`CRC`], usable in `const fn`s, which can't refer to statics
+const CONST_CRC: crc::Crc<u16> = crc::Crc::<u16>::new(&crc::CRC_16_OPENSAFETY_B);
+
+/// Convert a checksum into the bytes stored in a packet
+///
+/// The checksum is stored little-endian by default. Enabling the `crc-be` feature switches both
+/// encoding and decoding to big-endian, for interoperability with implementations using that order.
+pub(crate) const fn crc_to_bytes(checksum: u16) -> [u8; 2] {
+    if cfg!(feature = "crc-be") {
+        checksum.to_be_bytes()
+    } else {
+        checksum.to_le_bytes()
+    }
+}
+
+/// Convert the bytes stored in a packet back into a checksum
+///
+/// This is the inverse of [`crc_to_bytes`].
+pub(crate) fn crc_from_bytes(bytes: [u8; 2]) -> u16 {
+    if cfg!(feature = "crc-be") {
+        u16::from_be_bytes(bytes)
+    } else {
+        u16::from_le_bytes(bytes)
+    }
+}
+
+/// Build the control byte, holding the device ID and packet kind
+const fn control_byte(device_id: DeviceId, is_tm_packet: bool) -> u8 {
+    device_id.as_u8() << 2 | if is_tm_packet { 0 } else { 1 << 7 }
+}
+
+/// Maximum payload length supported by `encode_const`
+///
+/// This keeps unstuffed packets shorter than 254 bytes, so that COBS encoding always adds exactly
+/// one byte.
+const MAX_CONST_PAYLOAD_SIZE: usize = 253 - InternalPacket::OVERHEAD;
+
+/// Encode a packet without a sequence number, in a `const` context.
+///
+/// Panics (at compile time, if evaluated in a `const`) if the payload is longer than
+/// [`MAX_CONST_PAYLOAD_SIZE`] or `N` isn't the size of the encoded packet.
+const fn encode_const<const N: usize>(
+    device_id: DeviceId,
+    timestamp: Timestamp,
+    payload: &[u8],
+    is_tm_packet: bool,
+) -> [u8; N] {
+    assert!(
+        payload.len() <= MAX_CONST_PAYLOAD_SIZE,
+        "payload too long to encode in a const context"
+    );
+    let size = InternalPacket::OVERHEAD + payload.len();
+    assert!(N == size + 2, "N must be the encoded size of the packet");
+
+    let mut unstuffed = [0u8; InternalPacket::OVERHEAD + MAX_CONST_PAYLOAD_SIZE];
+    unstuffed[0] = ProtocolVersion::V1.as_u8();
+    // This conversion is sound since the payload length was checked above
+    unstuffed[1] = payload.len() as u8;
+    unstuffed[2] = control_byte(device_id, is_tm_packet);
+
+    let timestamp = timestamp.get().to_le_bytes();
+    let mut i = 0;
+    while i < 5 {
+        unstuffed[3 + i] = timestamp[i];
+        i += 1;
+    }
+
+    let mut i = 0;
+    while i < payload.len() {
+        unstuffed[8 + i] = payload[i];
+        i += 1;
+    }
+
+    let checksum = crc_to_bytes(CONST_CRC.checksum(unstuffed.split_at(size - 2).0));
+    unstuffed[size - 2] = checksum[0];
+    unstuffed[size - 1] = checksum[1];
+
+    // COBS encoding, simplified by the fact that there are never 254 consecutive non-zero bytes
+    let mut encoded = [0u8; N];
+    let mut code_idx = 0;
+    let mut code = 1;
+    let mut i = 0;
+    while i < size {
+        if unstuffed[i] == 0 {
+            encoded[code_idx] = code;
+            code_idx = i + 1;
+            code = 1;
+        } else {
+            encoded[i + 1] = unstuffed[i];
+            code += 1;
+        }
+        i += 1;
+    }
+    encoded[code_idx] = code;
+
+    // The last byte is left as 0, terminating the packet
+    encoded
+}
+
 impl InternalPacket {
     /// Maximum size of the buffer needed to encode a packet
     ///
     /// A buffer with this size can be used to `encode` any packet.
     // For encoding, we first write the header, payload and CRC to the buffer (overhead + payload size bytes).
     // Then, we use the remainder of the buffer as the COBS output buffer.
-    const MAX_ENCODE_BUFFER_SIZE: usize =
-        Self::OVERHEAD + Payload::MAX_SIZE + Self::MAX_ENCODED_SIZE;
+    const MAX_ENCODE_BUFFER_SIZE: usize = Self::MAX_SIZE + Self::MAX_ENCODED_SIZE;
 
     /// Size of the buffer needed to encode the packet
     ///
     /// A buffer passed to `encode` must be at least this size
     fn encode_buffer_size(&self) -> usize {
-        Self::OVERHEAD + self.payload.length() + self.encoded_size()
+        self.size() + self.encoded_size()
     }
 
     /// Write the header data into the provided buffer
     ///
     /// The number of written bytes is returned.
-    fn write_header_to_buffer(&self, buffer: &mut [u8], is_tm_packet: bool) -> usize {
+    fn write_header_to_buffer(
+        &self,
+        buffer: &mut [u8],
+        is_tm_packet: bool,
+    ) -> Result<usize, EncodeError> {
         let mut idx = 0;
 
-        buffer[idx] = self.version();
+        buffer[idx] = self.version().as_u8();
         idx += 1;
 
-        // This conversion from usize to u8 is sound since Payload guarantees its length can fit in a byte
-        buffer[idx] = self.payload().length() as u8;
+        let length = self.payload().length();
+        buffer[idx] = u8::try_from(length).map_err(|_| EncodeError::PayloadTooLong(length))?;
         idx += 1;
 
-        let control = *self.device_id() as u8;
-        let control = control << 2 | if is_tm_packet { 0 } else { 1 << 7 };
-        buffer[idx] = control;
+        buffer[idx] = control_byte(*self.device_id(), is_tm_packet) | self.control_flags();
         idx += 1;
 
+        if let Some(sequence) = self.sequence() {
+            buffer[idx..idx + 2].copy_from_slice(&sequence.to_le_bytes());
+            idx += 2;
+        }
+
         buffer[idx..idx + 5].copy_from_slice(&self.timestamp().get().to_le_bytes()[..5]);
 
-        idx + 5
+        Ok(idx + 5)
     }
 
     /// Write the payload data into the provided buffer
@@ -58,6 +274,66 @@ impl InternalPacket {
         payload.len()
     }
 
+    /// Write the header, payload and, unless disabled by the options, CRC into the provided buffer
+    ///
+    /// The number of written bytes is returned.
+    fn write_unstuffed_to_buffer<C: Crc16Variant>(
+        &self,
+        buffer: &mut [u8],
+        is_tm_packet: bool,
+        options: &EncodeOptions<C>,
+    ) -> Result<usize, EncodeError> {
+        let mut idx = self.write_header_to_buffer(buffer, is_tm_packet)?;
+
+        idx += self.write_payload_to_buffer(&mut buffer[idx..], self.payload.as_bytes());
+
+        if !options.trusted_link {
+            let checksum = C::checksum(&buffer[..idx]);
+
+            // Write the checksum after what's already written
+            buffer[idx..idx + 2].copy_from_slice(&crc_to_bytes(checksum));
+            idx += 2;
+        }
+
+        Ok(idx)
+    }
+
+    /// Encode the packet into the given buffer, prefixed by its length instead of COBS framed.
+    /// Returns a slice of the buffer containing the prefix and packet.
+    fn encode_raw<'a, C: Crc16Variant>(
+        &self,
+        buffer: &'a mut [u8],
+        is_tm_packet: bool,
+        options: &EncodeOptions<C>,
+    ) -> Result<&'a [u8], EncodeError> {
+        let size = if options.trusted_link {
+            self.size() - Self::CRC_SIZE
+        } else {
+            self.size()
+        };
+        let prefix_size = options.length_prefix.size();
+
+        let available = buffer.len();
+        let required = prefix_size + size;
+        if available < required {
+            return Err(EncodeError::BufferTooSmall {
+                required,
+                available,
+            });
+        }
+
+        match options.length_prefix {
+            LengthPrefix::U8 => {
+                buffer[0] = u8::try_from(size).map_err(|_| EncodeError::FrameTooLong(size))?;
+            }
+            // This conversion from usize to u16 is sound since packets are much shorter than 64 KiB
+            LengthPrefix::U16 => buffer[..2].copy_from_slice(&(size as u16).to_le_bytes()),
+        }
+        self.write_unstuffed_to_buffer(&mut buffer[prefix_size..], is_tm_packet, options)?;
+
+        Ok(&buffer[..required])
+    }
+
     /// Encode the packet into the given buffer. Returns a slice of the buffer containing the
     /// encoded packet.
     ///
@@ -67,6 +343,35 @@ impl InternalPacket {
         buffer: &'a mut [u8],
         is_tm_packet: bool,
     ) -> Result<&'a [u8], EncodeError> {
+        self.encode_with_options(buffer, is_tm_packet, &EncodeOptions::new())
+    }
+
+    /// Encode the packet into the given buffer, using the given options. Returns a slice of the
+    /// buffer containing the encoded packet.
+    ///
+    /// The provided buffer must be at least `Self::encode_buffer_size()` bytes long, regardless
+    /// of the options.
+    fn encode_with_options<'a, C: Crc16Variant>(
+        &self,
+        buffer: &'a mut [u8],
+        is_tm_packet: bool,
+        options: &EncodeOptions<C>,
+    ) -> Result<&'a [u8], EncodeError> {
+        let (_, encoded) = self.encode_parts(buffer, is_tm_packet, options)?;
+        Ok(encoded)
+    }
+
+    /// Encode the packet into the given buffer, using the given options. Returns slices of the
+    /// buffer containing the packet before and after COBS encoding.
+    ///
+    /// The provided buffer must be at least `Self::encode_buffer_size()` bytes long, regardless
+    /// of the options.
+    fn encode_parts<'a, C: Crc16Variant>(
+        &self,
+        buffer: &'a mut [u8],
+        is_tm_packet: bool,
+        options: &EncodeOptions<C>,
+    ) -> Result<(&'a [u8], &'a [u8]), EncodeError> {
         let available = buffer.len();
         let required = self.encode_buffer_size();
         if available < required {
@@ -76,21 +381,74 @@ impl InternalPacket {
             });
         }
 
-        let mut idx = self.write_header_to_buffer(buffer, is_tm_packet);
+        let idx = self.write_unstuffed_to_buffer(buffer, is_tm_packet, options)?;
 
-        idx += self.write_payload_to_buffer(&mut buffer[idx..], self.payload.as_bytes());
+        let (buffer_unencoded, cobs_buffer) = buffer.split_at_mut(idx);
+        let encoded = cobs::encode(buffer_unencoded, cobs_buffer);
+        cobs_buffer[encoded] = 0;
 
-        let checksum = CRC.checksum(&buffer[..idx]);
+        Ok((buffer_unencoded, &cobs_buffer[..encoded + 1]))
+    }
 
-        // Write the checksum after what's already written
-        buffer[idx..idx + 2].copy_from_slice(&checksum.to_le_bytes());
-        idx += 2;
+    /// Encode the packet, using the given options, passing the encoded frame to `sink` in
+    /// contiguous chunks. Returns the number of bytes passed to `sink`.
+    ///
+    /// Only the unstuffed packet is written to `scratch`, which must be at least `self.size()`
+    /// bytes long, regardless of the options.
+    fn encode_chunked<C: Crc16Variant>(
+        &self,
+        mut sink: impl FnMut(&[u8]),
+        scratch: &mut [u8],
+        is_tm_packet: bool,
+        options: &EncodeOptions<C>,
+    ) -> Result<usize, EncodeError> {
+        let available = scratch.len();
+        let required = self.size();
+        if available < required {
+            return Err(EncodeError::BufferTooSmall {
+                required,
+                available,
+            });
+        }
 
-        let (buffer_unencoded, cobs_buffer) = buffer.split_at_mut(idx);
-        let encoded = cobs::encode(buffer_unencoded, cobs_buffer);
-        buffer[idx + encoded] = 0;
+        let len = self.write_unstuffed_to_buffer(scratch, is_tm_packet, options)?;
+        let data = &scratch[..len];
 
-        Ok(&buffer[idx..(idx + encoded + 1)])
+        // COBS encoding, one block at a time. Each block is a code byte followed by a run of
+        // non-zero bytes, which is passed to the sink straight from the scratch buffer.
+        let mut written = 0;
+        let mut block = |run: &[u8]| {
+            // The run is at most 254 bytes long, so the code byte can't overflow
+            sink(&[run.len() as u8 + 1]);
+            if !run.is_empty() {
+                sink(run);
+            }
+            written += run.len() + 1;
+        };
+        let mut start = 0;
+        loop {
+            let rest = &data[start..];
+            match rest.iter().take(254).position(|&b| b == 0) {
+                // The zero ending the run is implied by the code byte
+                Some(idx) => {
+                    block(&rest[..idx]);
+                    start += idx + 1;
+                }
+                None => {
+                    let run = &rest[..rest.len().min(254)];
+                    block(run);
+                    // A full block doesn't imply a zero, so it's followed by another one, even if
+                    // it's empty, as with `cobs::encode`
+                    if run.len() < 254 {
+                        break;
+                    }
+                    start += run.len();
+                }
+            }
+        }
+
+        sink(&[0]);
+        Ok(written + 1)
     }
 }
 
@@ -114,6 +472,60 @@ impl TmPacket {
     pub fn encode<'a>(&self, buffer: &'a mut [u8]) -> Result<&'a [u8], EncodeError> {
         self.0.encode(buffer, true)
     }
+
+    /// Encode the packet into the given buffer, using the given options. Returns a slice of the
+    /// buffer containing the encoded packet.
+    ///
+    /// The provided buffer must be at least `Self::encode_buffer_size()` bytes long.
+    pub fn encode_with_options<'a, C: Crc16Variant>(
+        &self,
+        buffer: &'a mut [u8],
+        options: &EncodeOptions<C>,
+    ) -> Result<&'a [u8], EncodeError> {
+        self.0.encode_with_options(buffer, true, options)
+    }
+
+    /// Size of an encoded packet without a sequence number and with a payload of the given
+    /// length, in bytes, including the termination byte
+    ///
+    /// This is useful to size the array returned by [`Self::encode_const`].
+    pub const fn encoded_size_for(payload_length: usize) -> usize {
+        cobs::max_encoding_length(InternalPacket::OVERHEAD + payload_length) + 1
+    }
+
+    /// Encode a telemetry packet at compile time.
+    ///
+    /// This allows the encoded bytes of fixed packets to be stored in flash, rather than encoded at
+    /// runtime. `N` must be the size of the encoded packet, as given by
+    /// [`Self::encoded_size_for`]. The packet doesn't carry a sequence number.
+    ///
+    /// # Panics
+    /// Panics if the payload is longer than 243 bytes, or if `N` isn't the size of the encoded
+    /// packet. When evaluated in a `const`, this results in a compilation error.
+    ///
+    /// # Example
+    /// ```
+    /// use orbipacket::{TmPacket, DeviceId, Timestamp, Payload};
+    ///
+    /// const TIMESTAMP: Timestamp = match Timestamp::new(0) {
+    ///     Ok(timestamp) => timestamp,
+    ///     Err(_) => panic!("invalid timestamp"),
+    /// };
+    /// static PING: [u8; TmPacket::encoded_size_for(1)] =
+    ///     TmPacket::encode_const(DeviceId::System, TIMESTAMP, &[0x01]);
+    ///
+    /// let packet = TmPacket::new(DeviceId::System, TIMESTAMP, Payload::from_u8(0x01));
+    /// let mut buffer = [0u8; TmPacket::MAX_ENCODE_BUFFER_SIZE];
+    /// assert_eq!(PING, packet.encode(&mut buffer)?);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub const fn encode_const<const N: usize>(
+        device_id: DeviceId,
+        timestamp: Timestamp,
+        payload: &[u8],
+    ) -> [u8; N] {
+        encode_const(device_id, timestamp, payload, true)
+    }
 }
 
 impl TcPacket {
@@ -136,6 +548,60 @@ impl TcPacket {
     pub fn encode<'a>(&self, buffer: &'a mut [u8]) -> Result<&'a [u8], EncodeError> {
         self.0.encode(buffer, false)
     }
+
+    /// Encode the packet into the given buffer, using the given options. Returns a slice of the
+    /// buffer containing the encoded packet.
+    ///
+    /// The provided buffer must be at least `Self::encode_buffer_size()` bytes long.
+    pub fn encode_with_options<'a, C: Crc16Variant>(
+        &self,
+        buffer: &'a mut [u8],
+        options: &EncodeOptions<C>,
+    ) -> Result<&'a [u8], EncodeError> {
+        self.0.encode_with_options(buffer, false, options)
+    }
+
+    /// Size of an encoded packet without a sequence number and with a payload of the given
+    /// length, in bytes, including the termination byte
+    ///
+    /// This is useful to size the array returned by [`Self::encode_const`].
+    pub const fn encoded_size_for(payload_length: usize) -> usize {
+        cobs::max_encoding_length(InternalPacket::OVERHEAD + payload_length) + 1
+    }
+
+    /// Encode a telecommand packet at compile time.
+    ///
+    /// This allows the encoded bytes of fixed packets to be stored in flash, rather than encoded at
+    /// runtime. `N` must be the size of the encoded packet, as given by
+    /// [`Self::encoded_size_for`]. The packet doesn't carry a sequence number.
+    ///
+    /// # Panics
+    /// Panics if the payload is longer than 243 bytes, or if `N` isn't the size of the encoded
+    /// packet. When evaluated in a `const`, this results in a compilation error.
+    ///
+    /// # Example
+    /// ```
+    /// use orbipacket::{TcPacket, DeviceId, Timestamp, Payload};
+    ///
+    /// const TIMESTAMP: Timestamp = match Timestamp::new(0) {
+    ///     Ok(timestamp) => timestamp,
+    ///     Err(_) => panic!("invalid timestamp"),
+    /// };
+    /// static PING: [u8; TcPacket::encoded_size_for(1)] =
+    ///     TcPacket::encode_const(DeviceId::System, TIMESTAMP, &[0x01]);
+    ///
+    /// let packet = TcPacket::new(DeviceId::System, TIMESTAMP, Payload::from_u8(0x01));
+    /// let mut buffer = [0u8; TcPacket::MAX_ENCODE_BUFFER_SIZE];
+    /// assert_eq!(PING, packet.encode(&mut buffer)?);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub const fn encode_const<const N: usize>(
+        device_id: DeviceId,
+        timestamp: Timestamp,
+        payload: &[u8],
+    ) -> [u8; N] {
+        encode_const(device_id, timestamp, payload, false)
+    }
 }
 
 impl Packet {
@@ -164,6 +630,286 @@ impl Packet {
             Packet::TcPacket(packet) => packet.encode(buffer),
         }
     }
+
+    /// Encode the packet into the given buffer, using the given options. Returns a slice of the
+    /// buffer containing the encoded packet.
+    ///
+    /// The provided buffer must be at least `Self::encode_buffer_size()` bytes long.
+    ///
+    /// # Example
+    /// ```
+    /// use orbipacket::{Packet, TmPacket, DeviceId, Timestamp, Payload};
+    /// use orbipacket::{decode::DecodeOptions, encode::EncodeOptions};
+    ///
+    /// let packet = Packet::TmPacket(TmPacket::new(DeviceId::Gps, Timestamp::new(1)?, Payload::from_u8(1)));
+    /// let mut buffer = [0u8; Packet::MAX_ENCODE_BUFFER_SIZE];
+    ///
+    /// let encoded = packet.encode_with_options(&mut buffer, &EncodeOptions::new().trusted_link(true))?;
+    /// assert_eq!(encoded.len(), packet.encoded_size() - TmPacket::CRC_SIZE);
+    ///
+    /// let mut frame = encoded.to_vec();
+    /// let options = DecodeOptions::new().trusted_link(true);
+    /// assert_eq!(Packet::decode_single_with_options(&mut frame, &options)?, packet);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn encode_with_options<'a, C: Crc16Variant>(
+        &self,
+        buffer: &'a mut [u8],
+        options: &EncodeOptions<C>,
+    ) -> Result<&'a [u8], EncodeError> {
+        match self {
+            Packet::TmPacket(packet) => packet.encode_with_options(buffer, options),
+            Packet::TcPacket(packet) => packet.encode_with_options(buffer, options),
+        }
+    }
+
+    /// Encode the packet into the given buffer, returning both the raw packet and the final frame.
+    ///
+    /// The first slice holds the packet's fields and CRC, as written to the first part of the
+    /// buffer before COBS encoding, and the second holds the encoded frame, as returned by
+    /// [`Self::encode`]. This is useful to debug the wire format, or to verify the CRC with an
+    /// external tool.
+    ///
+    /// The provided buffer must be at least `Self::encode_buffer_size()` bytes long.
+    ///
+    /// # Example
+    /// ```
+    /// use orbipacket::{Packet, TmPacket, DeviceId, Timestamp, Payload};
+    ///
+    /// let packet = Packet::TmPacket(TmPacket::new(DeviceId::Gps, Timestamp::new(10)?, Payload::from_u8(7)));
+    /// let mut buffer = [0u8; Packet::MAX_ENCODE_BUFFER_SIZE];
+    ///
+    /// let (raw, encoded) = packet.encode_parts(&mut buffer)?;
+    ///
+    /// assert_eq!(raw[..9], [1, 1, 2 << 2, 10, 0, 0, 0, 0, 7]);
+    /// assert_eq!(raw.len(), packet.size());
+    /// assert_eq!(encoded.len(), packet.encoded_size());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn encode_parts<'a>(
+        &self,
+        buffer: &'a mut [u8],
+    ) -> Result<(&'a [u8], &'a [u8]), EncodeError> {
+        match self {
+            Packet::TmPacket(packet) => packet.0.encode_parts(buffer, true, &EncodeOptions::new()),
+            Packet::TcPacket(packet) => packet.0.encode_parts(buffer, false, &EncodeOptions::new()),
+        }
+    }
+
+    /// Encode the packet, passing the encoded frame to `sink` in contiguous chunks as they're
+    /// produced. Returns the total number of bytes passed to `sink`.
+    ///
+    /// Only the packet's fields and CRC are written to `scratch`, which must be at least
+    /// `self.size()` bytes long. The frame is then passed to `sink` as COBS code bytes, runs of
+    /// bytes borrowed from `scratch` and the final `0` delimiter, without ever being assembled.
+    /// This allows scattering a frame directly into DMA descriptors, saving a copy of it.
+    ///
+    /// # Example
+    /// ```
+    /// use orbipacket::{Packet, TmPacket, DeviceId, Timestamp, Payload};
+    ///
+    /// let packet = Packet::TmPacket(TmPacket::new(DeviceId::Gps, Timestamp::new(10)?, Payload::from_u8(7)));
+    /// let mut scratch = [0u8; TmPacket::MAX_SIZE];
+    /// let mut frame = Vec::new();
+    ///
+    /// let len = packet.encode_chunked(|chunk| frame.extend_from_slice(chunk), &mut scratch)?;
+    ///
+    /// let mut buffer = [0u8; Packet::MAX_ENCODE_BUFFER_SIZE];
+    /// assert_eq!(frame, packet.encode(&mut buffer)?);
+    /// assert_eq!(len, frame.len());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn encode_chunked(
+        &self,
+        sink: impl FnMut(&[u8]),
+        scratch: &mut [u8],
+    ) -> Result<usize, EncodeError> {
+        self.encode_chunked_with_options(sink, scratch, &EncodeOptions::new())
+    }
+
+    /// Encode the packet, passing the encoded frame to `sink` in contiguous chunks, using the
+    /// given options.
+    ///
+    /// See [`Self::encode_chunked`] for details.
+    pub fn encode_chunked_with_options<C: Crc16Variant>(
+        &self,
+        sink: impl FnMut(&[u8]),
+        scratch: &mut [u8],
+        options: &EncodeOptions<C>,
+    ) -> Result<usize, EncodeError> {
+        match self {
+            Packet::TmPacket(packet) => packet.0.encode_chunked(sink, scratch, true, options),
+            Packet::TcPacket(packet) => packet.0.encode_chunked(sink, scratch, false, options),
+        }
+    }
+
+    /// Encode the packet into the given buffer, prefixed by its length rather than COBS framed.
+    ///
+    /// This suits transports which already delimit messages, such as UDP datagrams or
+    /// length-delimited streams, where COBS stuffing is pure overhead. The packet is written
+    /// unstuffed, CRC included, after a two byte little endian length prefix. Packets framed this
+    /// way are decoded with [`Packet::decode_raw`].
+    ///
+    /// The provided buffer must be at least `LengthPrefix::U16.size() + self.size()` bytes long.
+    ///
+    /// # Example
+    /// ```
+    /// use orbipacket::{Packet, TmPacket, DeviceId, Timestamp, Payload};
+    ///
+    /// let packet = Packet::TmPacket(TmPacket::new(DeviceId::Gps, Timestamp::new(10)?, Payload::from_u8(7)));
+    /// let mut buffer = [0u8; 2 + TmPacket::MAX_SIZE];
+    ///
+    /// let framed = packet.encode_raw(&mut buffer)?;
+    ///
+    /// assert_eq!(framed.len(), 2 + packet.size());
+    /// assert_eq!(framed[..2], (packet.size() as u16).to_le_bytes());
+    /// assert_eq!(Packet::decode_raw(framed)?, (packet, framed.len()));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn encode_raw<'a>(&self, buffer: &'a mut [u8]) -> Result<&'a [u8], EncodeError> {
+        self.encode_raw_with_options(buffer, &EncodeOptions::new())
+    }
+
+    /// Encode the packet into the given buffer, prefixed by its length rather than COBS framed,
+    /// using the given options.
+    ///
+    /// See [`Self::encode_raw`] for details. The width of the prefix is set with
+    /// [`EncodeOptions::length_prefix`], and the CRC is omitted if
+    /// [`EncodeOptions::trusted_link`] is enabled.
+    ///
+    /// # Errors
+    /// Besides a buffer too small, an error variant is returned if the length of the packet
+    /// doesn't fit in a [`LengthPrefix::U8`] prefix.
+    pub fn encode_raw_with_options<'a, C: Crc16Variant>(
+        &self,
+        buffer: &'a mut [u8],
+        options: &EncodeOptions<C>,
+    ) -> Result<&'a [u8], EncodeError> {
+        match self {
+            Packet::TmPacket(packet) => packet.0.encode_raw(buffer, true, options),
+            Packet::TcPacket(packet) => packet.0.encode_raw(buffer, false, options),
+        }
+    }
+
+    /// Encode several packets back-to-back into the given buffer. Returns a slice of the buffer
+    /// containing the concatenated encoded packets.
+    ///
+    /// Since every encoded packet ends with a `0` byte, the output can be split back into
+    /// packets with [`Packet::decode_stateless`].
+    ///
+    /// The buffer must be large enough to hold the packets encoded so far plus the buffer
+    /// needed to encode the next one (see [`Self::encode_buffer_size`]).
+    ///
+    /// # Errors
+    /// If the buffer is too small, an error variant reporting the total required size is
+    /// returned, and nothing is encoded.
+    ///
+    /// # Example
+    /// ```
+    /// use orbipacket::{Packet, TmPacket, TcPacket, DeviceId, Timestamp, Payload};
+    ///
+    /// let packets = [
+    ///     Packet::TmPacket(TmPacket::new(DeviceId::Gps, Timestamp::new(1)?, Payload::from_u8(1))),
+    ///     Packet::TcPacket(TcPacket::new(DeviceId::System, Timestamp::new(2)?, Payload::new())),
+    /// ];
+    /// let mut buffer = [0u8; 2 * Packet::MAX_ENCODE_BUFFER_SIZE];
+    ///
+    /// let encoded = Packet::encode_batch(&packets, &mut buffer)?;
+    ///
+    /// assert_eq!(encoded.len(), packets[0].encoded_size() + packets[1].encoded_size());
+    /// assert_eq!(encoded.iter().filter(|&&b| b == 0).count(), 2);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn encode_batch<'a>(
+        packets: &[Packet],
+        buffer: &'a mut [u8],
+    ) -> Result<&'a [u8], EncodeError> {
+        let mut required = 0;
+        let mut encoded_total = 0;
+        for packet in packets {
+            required = required.max(encoded_total + packet.encode_buffer_size());
+            encoded_total += packet.encoded_size();
+        }
+
+        let available = buffer.len();
+        if available < required {
+            return Err(EncodeError::BufferTooSmall {
+                required,
+                available,
+            });
+        }
+
+        let mut idx = 0;
+        for packet in packets {
+            let encoded = packet.encode(&mut buffer[idx..])?.len();
+            // The encoded packet is written right after the unstuffed one, so move it back
+            let start = idx + packet.size();
+            buffer.copy_within(start..start + encoded, idx);
+            idx += encoded;
+        }
+
+        Ok(&buffer[..idx])
+    }
+}
+
+/// An encoder owning the buffer packets are encoded into, along with the encode options.
+///
+/// This is a convenience wrapper around [`Packet::encode_with_options`], for senders encoding
+/// packets in a loop: the same buffer is reused across calls to [`Self::encode_next`], so it
+/// doesn't have to be carried around separately. It does the same work as encoding into a
+/// buffer reused by the caller, and isn't any faster. `N` is the size of the buffer, which should
+/// be [`Packet::MAX_ENCODE_BUFFER_SIZE`] to be able to encode any packet.
+///
+/// # Example
+/// ```
+/// use orbipacket::{encode::Encoder, Packet, TmPacket, DeviceId, Timestamp, Payload};
+///
+/// let mut encoder = Encoder::<{ Packet::MAX_ENCODE_BUFFER_SIZE }>::new();
+///
+/// for i in 0..10u8 {
+///     let packet = Packet::TmPacket(TmPacket::new(DeviceId::Gps, Timestamp::new(0)?, Payload::from_u8(i)));
+///     let encoded = encoder.encode_next(&packet)?;
+///     assert_eq!(encoded.len(), packet.encoded_size());
+/// }
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct Encoder<const N: usize, C = OpenSafetyB> {
+    buffer: [u8; N],
+    options: EncodeOptions<C>,
+}
+
+impl<const N: usize> Encoder<N> {
+    /// Create an encoder using the default encode options.
+    pub const fn new() -> Self {
+        Self::with_options(EncodeOptions::new())
+    }
+}
+
+impl<const N: usize> Default for Encoder<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize, C: Crc16Variant> Encoder<N, C> {
+    /// Create an encoder using the given encode options.
+    pub const fn with_options(options: EncodeOptions<C>) -> Self {
+        Encoder {
+            buffer: [0; N],
+            options,
+        }
+    }
+
+    /// Encode a packet into the encoder's buffer. Returns a slice of the buffer containing the
+    /// encoded packet, which is valid until the next packet is encoded.
+    ///
+    /// # Errors
+    /// If the encoder's buffer is smaller than [`Packet::encode_buffer_size`], an error variant is
+    /// returned.
+    pub fn encode_next(&mut self, packet: &Packet) -> Result<&[u8], EncodeError> {
+        packet.encode_with_options(&mut self.buffer, &self.options)
+    }
 }
 
 #[cfg(test)]
@@ -171,14 +917,25 @@ mod tests {
     use core::borrow::BorrowMut;
 
     use crate::{
-        encode::EncodeError, DeviceId, InternalPacket, Packet, Payload, TcPacket, Timestamp,
-        TmPacket, VERSION,
+        encode::{EncodeError, EncodeOptions, Encoder},
+        DeviceId, InternalPacket, Packet, Payload, ProtocolVersion, TcPacket, Timestamp, TmPacket,
     };
 
+    const VERSION: u8 = ProtocolVersion::V1.as_u8();
+    const SEQUENCE_VERSION: u8 = ProtocolVersion::V2.as_u8();
+
     fn payload(data: u32) -> Payload {
         Payload::from_raw_bytes(data.to_le_bytes().as_slice()).unwrap()
     }
 
+    /// Adjust an expected frame, written with a little-endian CRC, to the configured CRC byte order
+    fn frame<const N: usize>(mut bytes: [u8; N]) -> [u8; N] {
+        if cfg!(feature = "crc-be") {
+            bytes.swap(N - 3, N - 2);
+        }
+        bytes
+    }
+
     #[test]
     fn encode_error_display() {
         let error = EncodeError::BufferTooSmall {
@@ -203,10 +960,10 @@ mod tests {
 
         assert_eq!(
             encoded,
-            &[
+            &frame([
                 0x03, VERSION, 0x04, 0x02, 0x0a, 0x01, 0x01, 0x01, 0x04, 0xEF, 0xCD, 0xAB, 0x03,
                 0xae, 0x90, 0x00
-            ][..]
+            ])[..]
         );
     }
 
@@ -221,10 +978,10 @@ mod tests {
 
         assert_eq!(
             encoded,
-            &[
+            &frame([
                 0x05, VERSION, 0x04, 0x80, 0x0a, 0x01, 0x01, 0x01, 0x04, 0xEF, 0xCD, 0xAB, 0x03,
                 0xc4, 0xa0, 0
-            ][..]
+            ])[..]
         );
     }
 
@@ -243,7 +1000,10 @@ mod tests {
         let EncodeError::BufferTooSmall {
             required,
             available,
-        } = error;
+        } = error
+        else {
+            unreachable!()
+        };
         assert_eq!(required, packet.encode_buffer_size());
         assert_eq!(available, buffer.len());
     }
@@ -259,10 +1019,10 @@ mod tests {
 
         assert_eq!(
             encoded,
-            &[
+            &frame([
                 0x03, VERSION, 0x04, 0x02, 0x0a, 0x01, 0x01, 0x01, 0x04, 0xEF, 0xCD, 0xAB, 0x03,
                 0xae, 0x90, 0x00
-            ][..]
+            ])[..]
         );
     }
 
@@ -277,10 +1037,10 @@ mod tests {
 
         assert_eq!(
             encoded,
-            &[
+            &frame([
                 0x05, VERSION, 0x04, 0x80, 0x0a, 0x01, 0x01, 0x01, 0x04, 0xEF, 0xCD, 0xAB, 0x03,
                 0xc4, 0xa0, 0
-            ][..]
+            ])[..]
         );
     }
 
@@ -295,10 +1055,10 @@ mod tests {
 
         assert_eq!(
             encoded,
-            &[
+            &frame([
                 0x03, VERSION, 0x04, 0x02, 0x0a, 0x01, 0x01, 0x01, 0x04, 0xEF, 0xCD, 0xAB, 0x03,
                 0xae, 0x90, 0x00
-            ][..]
+            ])[..]
         );
     }
 
@@ -313,10 +1073,306 @@ mod tests {
 
         assert_eq!(
             encoded,
-            &[
+            &frame([
                 0x05, VERSION, 0x04, 0x80, 0x0a, 0x01, 0x01, 0x01, 0x04, 0xEF, 0xCD, 0xAB, 0x03,
                 0xc4, 0xa0, 0
-            ][..]
+            ])[..]
+        );
+    }
+
+    #[test]
+    fn crc_bytes_round_trip() {
+        let bytes = super::crc_to_bytes(0x1234);
+
+        assert_eq!(super::crc_from_bytes(bytes), 0x1234);
+        if cfg!(feature = "crc-be") {
+            assert_eq!(bytes, [0x12, 0x34]);
+        } else {
+            assert_eq!(bytes, [0x34, 0x12]);
+        }
+    }
+
+    #[test]
+    fn encode_then_decode_is_symmetric_under_crc_byte_order() {
+        let payload = payload(0xABCDEFu32);
+        let packet = Packet::TcPacket(TcPacket::new(DeviceId::Gps, Timestamp(10), payload));
+        let mut buffer = [0u8; InternalPacket::MAX_ENCODE_BUFFER_SIZE];
+
+        let encoded = packet.encode(buffer.borrow_mut()).unwrap();
+        let mut frame = [0u8; InternalPacket::MAX_ENCODED_SIZE];
+        let frame = &mut frame[..encoded.len()];
+        frame.copy_from_slice(encoded);
+
+        assert_eq!(Packet::decode_single(frame).unwrap(), packet);
+    }
+
+    #[test]
+    fn internal_packet_encode_with_sequence_writes_sequence_after_control() {
+        let payload = payload(0xABCDEFu32);
+        let packet =
+            InternalPacket::new(DeviceId::System, Timestamp(10), payload).with_sequence(0x0201);
+
+        let mut buffer = [0u8; InternalPacket::MAX_ENCODE_BUFFER_SIZE];
+
+        let encoded = packet.encode(buffer.borrow_mut(), true).unwrap();
+
+        assert_eq!(encoded.len(), packet.encoded_size());
+        assert_eq!(
+            &encoded[..7],
+            &[0x03, SEQUENCE_VERSION, 0x04, 0x04, 0x01, 0x02, 0x0a][..]
+        );
+    }
+
+    #[test]
+    fn packet_encode_batch_concatenates_frames() {
+        let tm_packet = Packet::TmPacket(TmPacket::new(
+            DeviceId::System,
+            Timestamp(10),
+            payload(0xABCDEFu32),
+        ));
+        let tc_packet = Packet::TcPacket(TcPacket::new(
+            DeviceId::System,
+            Timestamp(10),
+            payload(0xABCDEFu32),
+        ));
+
+        let mut buffer = [0u8; 2 * InternalPacket::MAX_ENCODE_BUFFER_SIZE];
+
+        let encoded = Packet::encode_batch(&[tm_packet, tc_packet], buffer.borrow_mut()).unwrap();
+
+        let mut expected = [0u8; 32];
+        expected[..16].copy_from_slice(&frame([
+            0x03, VERSION, 0x04, 0x02, 0x0a, 0x01, 0x01, 0x01, 0x04, 0xEF, 0xCD, 0xAB, 0x03, 0xae,
+            0x90, 0x00,
+        ]));
+        expected[16..].copy_from_slice(&frame([
+            0x05, VERSION, 0x04, 0x80, 0x0a, 0x01, 0x01, 0x01, 0x04, 0xEF, 0xCD, 0xAB, 0x03, 0xc4,
+            0xa0, 0,
+        ]));
+        assert_eq!(encoded, &expected[..]);
+    }
+
+    #[test]
+    fn packet_encode_batch_buffer_too_small() {
+        let packet = Packet::TmPacket(TmPacket::new(
+            DeviceId::System,
+            Timestamp(10),
+            payload(0xABCDEFu32),
+        ));
+        let packets = [packet; 3];
+
+        let mut buffer = [0u8; 40];
+
+        let result = Packet::encode_batch(&packets, buffer.borrow_mut());
+
+        let Err(EncodeError::BufferTooSmall {
+            required,
+            available,
+        }) = result
+        else {
+            panic!("Encoding didn't fail")
+        };
+        assert_eq!(
+            required,
+            2 * packet.encoded_size() + packet.encode_buffer_size()
+        );
+        assert_eq!(available, 40);
+    }
+
+    #[test]
+    fn packet_encode_batch_empty_is_empty() {
+        let mut buffer = [0u8; 0];
+
+        let encoded = Packet::encode_batch(&[], buffer.borrow_mut()).unwrap();
+
+        assert!(encoded.is_empty());
+    }
+
+    const CONST_TM_PACKET: [u8; TmPacket::encoded_size_for(4)] =
+        TmPacket::encode_const(DeviceId::System, Timestamp(10), &0xABCDEFu32.to_le_bytes());
+
+    const CONST_TC_PACKET: [u8; TcPacket::encoded_size_for(0)] =
+        TcPacket::encode_const(DeviceId::Mission2, Timestamp(0x12_3456_789A), &[]);
+
+    #[test]
+    fn encode_const_matches_encode() {
+        let tm_packet = TmPacket::new(DeviceId::System, Timestamp(10), payload(0xABCDEFu32));
+        let tc_packet = TcPacket::new(
+            DeviceId::Mission2,
+            Timestamp(0x12_3456_789A),
+            Payload::new(),
+        );
+        let mut buffer = [0u8; InternalPacket::MAX_ENCODE_BUFFER_SIZE];
+
+        assert_eq!(
+            CONST_TM_PACKET,
+            tm_packet.encode(buffer.borrow_mut()).unwrap()
+        );
+        assert_eq!(
+            CONST_TC_PACKET,
+            tc_packet.encode(buffer.borrow_mut()).unwrap()
+        );
+    }
+
+    #[test]
+    fn encode_const_matches_encode_for_longest_payload() {
+        let bytes = [0x5A; super::MAX_CONST_PAYLOAD_SIZE];
+        let packet = TmPacket::new(
+            DeviceId::Gps,
+            Timestamp(1),
+            Payload::from_raw_bytes(bytes).unwrap(),
+        );
+        let mut buffer = [0u8; InternalPacket::MAX_ENCODE_BUFFER_SIZE];
+
+        let encoded: [u8; TmPacket::encoded_size_for(super::MAX_CONST_PAYLOAD_SIZE)] =
+            TmPacket::encode_const(DeviceId::Gps, Timestamp(1), &bytes);
+
+        assert_eq!(encoded, packet.encode(buffer.borrow_mut()).unwrap());
+    }
+
+    #[test]
+    #[should_panic]
+    fn encode_const_panics_on_wrong_size() {
+        let _: [u8; 4] = TmPacket::encode_const(DeviceId::Gps, Timestamp(1), &[]);
+    }
+
+    #[test]
+    fn encoder_matches_per_call_encode() {
+        let packets = [
+            Packet::TmPacket(TmPacket::new(
+                DeviceId::Gps,
+                Timestamp(10),
+                payload(0xABCDEFu32),
+            )),
+            Packet::TcPacket(TcPacket::new(
+                DeviceId::System,
+                Timestamp(0),
+                Payload::new(),
+            )),
+        ];
+        let mut encoder = Encoder::<{ Packet::MAX_ENCODE_BUFFER_SIZE }>::new();
+
+        for packet in &packets {
+            let mut buffer = [0u8; Packet::MAX_ENCODE_BUFFER_SIZE];
+            let expected = packet.encode(&mut buffer).unwrap();
+
+            assert_eq!(encoder.encode_next(packet).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn encoder_uses_options() {
+        let packet = Packet::TmPacket(TmPacket::new(DeviceId::Gps, Timestamp(10), Payload::new()));
+        let mut encoder = Encoder::<{ Packet::MAX_ENCODE_BUFFER_SIZE }, _>::with_options(
+            EncodeOptions::new().trusted_link(true),
         );
+
+        let encoded = encoder.encode_next(&packet).unwrap();
+
+        assert_eq!(encoded.len(), packet.encoded_size() - TmPacket::CRC_SIZE);
+    }
+
+    #[test]
+    fn encoder_rejects_small_buffer() {
+        let packet = Packet::TmPacket(TmPacket::new(DeviceId::Gps, Timestamp(10), Payload::new()));
+        let mut encoder = Encoder::<16>::new();
+
+        let result = encoder.encode_next(&packet);
+
+        assert!(matches!(
+            result,
+            Err(EncodeError::BufferTooSmall { available: 16, .. })
+        ));
+    }
+
+    #[test]
+    fn encode_parts_returns_raw_and_encoded_frames() {
+        use crate::checksum::{Crc16Variant, OpenSafetyB};
+
+        let packet = Packet::TcPacket(TcPacket::new(
+            DeviceId::Gps,
+            Timestamp(10),
+            payload(0xABCDEFu32),
+        ));
+        let mut expected_buffer = [0u8; Packet::MAX_ENCODE_BUFFER_SIZE];
+        let expected = packet.encode(&mut expected_buffer).unwrap();
+        let mut buffer = [0u8; Packet::MAX_ENCODE_BUFFER_SIZE];
+
+        let (raw, encoded) = packet.encode_parts(&mut buffer).unwrap();
+
+        let (fields, crc) = raw.split_at(raw.len() - TcPacket::CRC_SIZE);
+        assert_eq!(
+            super::crc_from_bytes(crc.try_into().unwrap()),
+            OpenSafetyB::checksum(fields)
+        );
+        assert_eq!(encoded, expected);
+        let mut decoded = [0u8; Packet::MAX_ENCODE_BUFFER_SIZE];
+        let decoded_len = cobs::decode(&encoded[..encoded.len() - 1], &mut decoded).unwrap();
+        assert_eq!(&decoded[..decoded_len], raw);
+    }
+
+    #[test]
+    fn encode_chunked_rejects_small_scratch() {
+        let packet = Packet::TmPacket(TmPacket::new(DeviceId::Gps, Timestamp(10), payload(7)));
+        let mut scratch = [0u8; 4];
+
+        let result = packet.encode_chunked(|_| panic!("nothing should be emitted"), &mut scratch);
+
+        assert!(matches!(
+            result,
+            Err(EncodeError::BufferTooSmall { required, available: 4 }) if required == packet.size()
+        ));
+    }
+
+    #[test]
+    fn encode_chunked_splits_long_runs() {
+        // Long enough for a run of more than 254 non-zero bytes, which takes two COBS blocks
+        let packet = Packet::TmPacket(TmPacket::new(
+            DeviceId::Gps,
+            Timestamp(0x0101010101),
+            Payload::from_raw_bytes([0xAB; Payload::MAX_SIZE]).unwrap(),
+        ));
+        let mut buffer = [0u8; Packet::MAX_ENCODE_BUFFER_SIZE];
+        let expected = packet.encode(&mut buffer).unwrap();
+        let mut scratch = [0u8; TmPacket::MAX_SIZE];
+        let mut frame = Vec::new();
+
+        packet
+            .encode_chunked(|chunk| frame.extend_from_slice(chunk), &mut scratch)
+            .unwrap();
+
+        assert_eq!(frame, expected);
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn encode_chunked_matches_encode(
+            bytes in proptest::collection::vec(
+                proptest::prop_oneof![proptest::prelude::Just(0u8), 1..=255u8],
+                0..=Payload::MAX_SIZE,
+            ),
+            sequence in proptest::option::of(proptest::prelude::any::<u16>()),
+        ) {
+            let mut packet = TcPacket::new(
+                DeviceId::Gps,
+                Timestamp(10),
+                Payload::from_raw_bytes(&bytes).unwrap(),
+            );
+            if let Some(sequence) = sequence {
+                packet = packet.with_sequence(sequence);
+            }
+            let packet = Packet::TcPacket(packet);
+            let mut buffer = [0u8; Packet::MAX_ENCODE_BUFFER_SIZE];
+            let expected = packet.encode(&mut buffer).unwrap();
+            let mut scratch = [0u8; TcPacket::MAX_SIZE];
+            let mut frame = Vec::new();
+
+            let len = packet
+                .encode_chunked(|chunk| frame.extend_from_slice(chunk), &mut scratch)
+                .unwrap();
+
+            proptest::prop_assert_eq!(&frame[..], expected);
+            proptest::prop_assert_eq!(len, frame.len());
+        }
     }
 }
@@ -0,0 +1,451 @@
+//! Splitting of data larger than [`Payload::MAX_SIZE`] across several packets.
+//!
+//! Each fragment is a regular [`TmPacket`] whose payload starts with a small header:
+//! - 1 byte for the index of the fragment
+//! - 1 byte for the total number of fragments
+//!
+//! The remainder of the payload carries a chunk of the original data. All fragments of the same
+//! data share the device ID and timestamp they were created with, which is how a [`Reassembler`]
+//! tells transfers apart.
+//!
+//! # Example
+//! ```
+//! use orbipacket::{DeviceId, Timestamp};
+//! use orbipacket::fragment::{fragment, Reassembler};
+//!
+//! let data = [0xAB; 600];
+//! let mut reassembler = Reassembler::<1024>::new();
+//! let mut reassembled = None;
+//!
+//! for packet in fragment(DeviceId::Camera, Timestamp::new(42)?, &data)? {
+//!     if let Some(bytes) = reassembler.push(&packet)? {
+//!         reassembled = Some(bytes.len());
+//!     }
+//! }
+//!
+//! assert_eq!(reassembled, Some(data.len()));
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::{DeviceId, Payload, Timestamp, TmPacket};
+
+/// Error that can occur when fragmenting or reassembling data
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum FragmentError {
+    /// The data is too long to be split into at most [`MAX_FRAGMENTS`] fragments
+    #[error("data too long to fragment: {0} bytes")]
+    DataTooLong(usize),
+    /// The payload is too short to contain a fragment header, or the header is inconsistent
+    #[error("malformed fragment header (index {index}, total {total})")]
+    MalformedHeader { index: u8, total: u8 },
+    /// A fragment was received for a transfer that reported a different number of fragments
+    #[error("fragment count mismatch (expected {expected}, found {found})")]
+    TotalMismatch { expected: u8, found: u8 },
+    /// The same fragment was received twice for a transfer
+    #[error("duplicate fragment {0}")]
+    DuplicateFragment(u8),
+    /// A new transfer started before the previous one from the same device was complete.
+    ///
+    /// The incomplete transfer is discarded, but the fragment that caused this error is not
+    /// consumed; pushing it again starts the new transfer.
+    #[error("incomplete transfer from {device_id}: received {received} of {total} fragments")]
+    MissingFragments {
+        device_id: DeviceId,
+        received: u8,
+        total: u8,
+    },
+    /// The reassembled data doesn't fit in the reassembler's buffer
+    #[error(
+        "reassembly buffer too small: required {required} bytes, but only {available} available"
+    )]
+    BufferTooSmall { required: usize, available: usize },
+}
+
+/// Size of the fragment header, in bytes
+pub const HEADER_SIZE: usize = 2;
+
+/// Maximum number of data bytes carried by a single fragment
+pub const MAX_CHUNK_SIZE: usize = Payload::MAX_SIZE - HEADER_SIZE;
+
+/// Maximum number of fragments a piece of data can be split into
+pub const MAX_FRAGMENTS: usize = u8::MAX as usize;
+
+/// Maximum size of data that can be fragmented, in bytes
+pub const MAX_DATA_SIZE: usize = MAX_FRAGMENTS * MAX_CHUNK_SIZE;
+
+/// Split `data` into a sequence of telemetry packets.
+///
+/// Empty data results in a single fragment with no data bytes.
+///
+/// # Errors
+/// If `data` is longer than [`MAX_DATA_SIZE`], an error variant is returned.
+pub fn fragment(
+    device_id: DeviceId,
+    timestamp: Timestamp,
+    data: &[u8],
+) -> Result<Fragments<'_>, FragmentError> {
+    if data.len() > MAX_DATA_SIZE {
+        return Err(FragmentError::DataTooLong(data.len()));
+    }
+
+    // Conversion is sound since the length check above bounds the count to MAX_FRAGMENTS
+    let total = data.len().div_ceil(MAX_CHUNK_SIZE).max(1) as u8;
+
+    Ok(Fragments {
+        device_id,
+        timestamp,
+        data,
+        index: 0,
+        total,
+    })
+}
+
+/// Iterator over the fragments of some data, created by [`fragment`]
+#[derive(Clone, Debug)]
+pub struct Fragments<'a> {
+    device_id: DeviceId,
+    timestamp: Timestamp,
+    data: &'a [u8],
+    index: u8,
+    total: u8,
+}
+
+impl Iterator for Fragments<'_> {
+    type Item = TmPacket;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.total {
+            return None;
+        }
+
+        let start = self.index as usize * MAX_CHUNK_SIZE;
+        let end = (start + MAX_CHUNK_SIZE).min(self.data.len());
+        let chunk = &self.data[start..end];
+
+        let mut bytes = [0u8; Payload::MAX_SIZE];
+        bytes[0] = self.index;
+        bytes[1] = self.total;
+        bytes[HEADER_SIZE..][..chunk.len()].copy_from_slice(chunk);
+
+        self.index += 1;
+
+        // Unwrapping is safe here because a chunk is at most MAX_CHUNK_SIZE bytes long
+        let payload = Payload::from_raw_bytes(&bytes[..HEADER_SIZE + chunk.len()]).unwrap();
+
+        Some(TmPacket::new(self.device_id, self.timestamp, payload))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.total - self.index) as usize;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for Fragments<'_> {}
+
+/// Number of distinct device IDs, and thus of concurrent transfers a [`Reassembler`] tracks
+const DEVICE_COUNT: usize = 16;
+
+/// State of the transfer from a single device
+#[derive(Copy, Clone, Debug)]
+struct Slot<const N: usize> {
+    timestamp: Timestamp,
+    total: u8,
+    received: u8,
+    /// One bit per fragment index
+    bitmap: [u32; 8],
+    /// Length of the data, known once the last fragment is received
+    length: usize,
+    buffer: [u8; N],
+}
+
+impl<const N: usize> Slot<N> {
+    const EMPTY: Self = Slot {
+        timestamp: Timestamp(0),
+        total: 0,
+        received: 0,
+        bitmap: [0; 8],
+        length: 0,
+        buffer: [0; N],
+    };
+
+    fn is_active(&self) -> bool {
+        self.total != 0
+    }
+
+    fn start(&mut self, timestamp: Timestamp, total: u8) {
+        self.timestamp = timestamp;
+        self.total = total;
+        self.received = 0;
+        self.bitmap = [0; 8];
+        self.length = 0;
+    }
+
+    fn has(&self, index: u8) -> bool {
+        self.bitmap[index as usize / 32] & (1 << (index % 32)) != 0
+    }
+
+    fn mark(&mut self, index: u8) {
+        self.bitmap[index as usize / 32] |= 1 << (index % 32);
+        self.received += 1;
+    }
+}
+
+/// Reconstructs data split by [`fragment`].
+///
+/// Fragments are collected per device, so transfers from different devices can be interleaved.
+/// Within a transfer, fragments may arrive in any order. The const parameter `N` is the maximum
+/// size of reassembled data, in bytes, for each device.
+///
+/// A transfer is identified by the timestamp shared by its fragments. If a fragment with a
+/// different timestamp arrives while a transfer from the same device is incomplete, the old
+/// transfer is discarded and a [`FragmentError::MissingFragments`] error is reported.
+#[derive(Clone, Debug)]
+pub struct Reassembler<const N: usize> {
+    slots: [Slot<N>; DEVICE_COUNT],
+}
+
+impl<const N: usize> Reassembler<N> {
+    /// Create a reassembler with no transfers in progress.
+    pub fn new() -> Self {
+        Reassembler {
+            slots: [Slot::EMPTY; DEVICE_COUNT],
+        }
+    }
+
+    /// Add a fragment to the transfer from its device.
+    ///
+    /// Returns the reassembled data once all fragments of the transfer have been received, and
+    /// `None` otherwise.
+    ///
+    /// # Errors
+    /// An error variant is returned if:
+    /// - the payload doesn't contain a valid fragment header;
+    /// - the fragment disagrees with the transfer on the number of fragments;
+    /// - the fragment was already received;
+    /// - a previous transfer from the same device is left incomplete;
+    /// - the data doesn't fit in `N` bytes.
+    pub fn push(&mut self, packet: &TmPacket) -> Result<Option<&[u8]>, FragmentError> {
+        let bytes = packet.payload().as_bytes();
+        let (index, total) = match bytes {
+            [index, total, ..] if index < total => (*index, *total),
+            [index, total, ..] => {
+                return Err(FragmentError::MalformedHeader {
+                    index: *index,
+                    total: *total,
+                })
+            }
+            _ => return Err(FragmentError::MalformedHeader { index: 0, total: 0 }),
+        };
+        let chunk = &bytes[HEADER_SIZE..];
+        let is_last = index == total - 1;
+
+        if (!is_last && chunk.len() != MAX_CHUNK_SIZE) || chunk.len() > MAX_CHUNK_SIZE {
+            return Err(FragmentError::MalformedHeader { index, total });
+        }
+
+        let start = index as usize * MAX_CHUNK_SIZE;
+        let required = start + chunk.len();
+        if required > N {
+            return Err(FragmentError::BufferTooSmall {
+                required,
+                available: N,
+            });
+        }
+
+        let device_id = *packet.device_id();
+        let slot = &mut self.slots[device_id.as_u8() as usize];
+
+        if !slot.is_active() {
+            slot.start(*packet.timestamp(), total);
+        } else if slot.timestamp != *packet.timestamp() {
+            let error = FragmentError::MissingFragments {
+                device_id,
+                received: slot.received,
+                total: slot.total,
+            };
+            slot.total = 0;
+            return Err(error);
+        } else if slot.total != total {
+            return Err(FragmentError::TotalMismatch {
+                expected: slot.total,
+                found: total,
+            });
+        } else if slot.has(index) {
+            return Err(FragmentError::DuplicateFragment(index));
+        }
+
+        slot.buffer[start..required].copy_from_slice(chunk);
+        slot.mark(index);
+        if is_last {
+            slot.length = required;
+        }
+
+        if slot.received == slot.total {
+            slot.total = 0;
+            Ok(Some(&slot.buffer[..slot.length]))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Discard the transfer in progress from the given device, if any.
+    pub fn reset(&mut self, device_id: DeviceId) {
+        self.slots[device_id.as_u8() as usize].total = 0;
+    }
+}
+
+impl<const N: usize> Default for Reassembler<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data<const L: usize>() -> [u8; L] {
+        core::array::from_fn(|i| i as u8)
+    }
+
+    #[test]
+    fn fragment_splits_data_into_chunks() {
+        let data = data::<600>();
+        let fragments = fragment(DeviceId::Camera, Timestamp(1), &data).unwrap();
+
+        assert_eq!(fragments.len(), 3);
+        let lengths: Vec<_> = fragments.map(|p| p.payload().length()).collect();
+        assert_eq!(lengths, [255, 255, 600 - 2 * MAX_CHUNK_SIZE + HEADER_SIZE]);
+    }
+
+    #[test]
+    fn fragment_empty_data_yields_single_fragment() {
+        let mut fragments = fragment(DeviceId::Camera, Timestamp(1), &[]).unwrap();
+
+        assert_eq!(fragments.next().unwrap().payload().as_bytes(), [0, 1]);
+        assert!(fragments.next().is_none());
+    }
+
+    #[test]
+    fn fragment_rejects_data_too_long() {
+        let data = [0u8; MAX_DATA_SIZE + 1];
+
+        let result = fragment(DeviceId::Camera, Timestamp(1), &data);
+
+        assert_eq!(
+            result.unwrap_err(),
+            FragmentError::DataTooLong(MAX_DATA_SIZE + 1)
+        );
+    }
+
+    #[test]
+    fn reassembler_reconstructs_out_of_order_fragments() {
+        let data = data::<700>();
+        let mut packets: Vec<_> = fragment(DeviceId::Camera, Timestamp(1), &data)
+            .unwrap()
+            .collect();
+        packets.reverse();
+        let mut reassembler = Reassembler::<1024>::new();
+
+        assert_eq!(reassembler.push(&packets[0]).unwrap(), None);
+        assert_eq!(reassembler.push(&packets[1]).unwrap(), None);
+        assert_eq!(reassembler.push(&packets[2]).unwrap(), Some(&data[..]));
+    }
+
+    #[test]
+    fn reassembler_interleaves_devices() {
+        let camera = data::<300>();
+        let gps = [7u8; 260];
+        let camera_packets: Vec<_> = fragment(DeviceId::Camera, Timestamp(1), &camera)
+            .unwrap()
+            .collect();
+        let gps_packets: Vec<_> = fragment(DeviceId::Gps, Timestamp(2), &gps)
+            .unwrap()
+            .collect();
+        let mut reassembler = Reassembler::<512>::new();
+
+        assert_eq!(reassembler.push(&camera_packets[0]).unwrap(), None);
+        assert_eq!(reassembler.push(&gps_packets[0]).unwrap(), None);
+        assert_eq!(
+            reassembler.push(&camera_packets[1]).unwrap(),
+            Some(&camera[..])
+        );
+        assert_eq!(reassembler.push(&gps_packets[1]).unwrap(), Some(&gps[..]));
+    }
+
+    #[test]
+    fn reassembler_reports_missing_fragments() {
+        let data = data::<600>();
+        let first: Vec<_> = fragment(DeviceId::Camera, Timestamp(1), &data)
+            .unwrap()
+            .collect();
+        let second: Vec<_> = fragment(DeviceId::Camera, Timestamp(2), &data[..10])
+            .unwrap()
+            .collect();
+        let mut reassembler = Reassembler::<1024>::new();
+
+        reassembler.push(&first[0]).unwrap();
+        let error = reassembler.push(&second[0]).unwrap_err();
+
+        assert_eq!(
+            error,
+            FragmentError::MissingFragments {
+                device_id: DeviceId::Camera,
+                received: 1,
+                total: 3
+            }
+        );
+        // The fragment wasn't consumed, so pushing it again starts the new transfer
+        assert_eq!(reassembler.push(&second[0]).unwrap(), Some(&data[..10]));
+    }
+
+    #[test]
+    fn reassembler_rejects_duplicate_fragment() {
+        let data = data::<600>();
+        let packets: Vec<_> = fragment(DeviceId::Camera, Timestamp(1), &data)
+            .unwrap()
+            .collect();
+        let mut reassembler = Reassembler::<1024>::new();
+
+        reassembler.push(&packets[1]).unwrap();
+
+        assert_eq!(
+            reassembler.push(&packets[1]).unwrap_err(),
+            FragmentError::DuplicateFragment(1)
+        );
+    }
+
+    #[test]
+    fn reassembler_rejects_malformed_header() {
+        let mut reassembler = Reassembler::<1024>::new();
+        let packet = TmPacket::new(
+            DeviceId::Camera,
+            Timestamp(1),
+            Payload::from_raw_bytes([3, 3]).unwrap(),
+        );
+
+        assert_eq!(
+            reassembler.push(&packet).unwrap_err(),
+            FragmentError::MalformedHeader { index: 3, total: 3 }
+        );
+    }
+
+    #[test]
+    fn reassembler_rejects_data_larger_than_buffer() {
+        let data = data::<600>();
+        let packets: Vec<_> = fragment(DeviceId::Camera, Timestamp(1), &data)
+            .unwrap()
+            .collect();
+        let mut reassembler = Reassembler::<300>::new();
+
+        assert_eq!(
+            reassembler.push(&packets[1]).unwrap_err(),
+            FragmentError::BufferTooSmall {
+                required: 2 * MAX_CHUNK_SIZE,
+                available: 300
+            }
+        );
+    }
+}
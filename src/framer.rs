@@ -0,0 +1,192 @@
+//! Buffering of incoming bytes into frames, for byte-oriented links such as UART.
+
+/// A fixed-capacity ring buffer which splits incoming bytes into COBS frames.
+///
+/// Bytes can be appended in arbitrarily sized chunks, e.g. from DMA half-transfers, and complete
+/// frames are popped in the order they were received. Chunks may contain several frames, and a
+/// frame may straddle several chunks.
+///
+/// Frames are yielded without their terminating `0` byte, just like with
+/// [`frames`](crate::decode::frames). Since a frame must fit in the buffer, `N` should be at least
+/// [`TmPacket::MAX_ENCODED_SIZE`](crate::TmPacket::MAX_ENCODED_SIZE).
+///
+/// # Example
+/// ```
+/// # use orbipacket::framer::FrameRingBuffer;
+/// let mut buffer = FrameRingBuffer::<8>::new();
+///
+/// assert_eq!(buffer.extend_from_slice(&[0x02, 0xAA, 0x00, 0x03]), 4);
+/// assert_eq!(buffer.pop_frame(), Some(&[0x02, 0xAA][..]));
+/// assert_eq!(buffer.pop_frame(), None);
+///
+/// assert_eq!(buffer.extend_from_slice(&[0xBB, 0xCC, 0x00]), 3);
+/// assert_eq!(buffer.pop_frame(), Some(&[0x03, 0xBB, 0xCC][..]));
+/// ```
+#[derive(Clone, Debug)]
+pub struct FrameRingBuffer<const N: usize> {
+    data: [u8; N],
+    head: usize,
+    len: usize,
+}
+
+impl<const N: usize> FrameRingBuffer<N> {
+    /// Create an empty buffer.
+    pub const fn new() -> Self {
+        FrameRingBuffer {
+            data: [0; N],
+            head: 0,
+            len: 0,
+        }
+    }
+
+    /// Maximum number of bytes the buffer can hold
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Number of bytes currently held by the buffer
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check if the buffer holds no bytes
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Discard all the bytes held by the buffer.
+    ///
+    /// This is needed to recover if the buffer fills up without holding a complete frame, which
+    /// happens if a frame is larger than the buffer, or its terminator was lost.
+    pub fn clear(&mut self) {
+        self.head = 0;
+        self.len = 0;
+    }
+
+    /// Append bytes to the buffer, returning how many were accepted.
+    ///
+    /// If there isn't enough room for all the bytes, only the first ones are accepted. A return
+    /// value smaller than `bytes.len()` thus signals an overflow, and the remaining bytes should
+    /// be offered again after popping some frames.
+    pub fn extend_from_slice(&mut self, bytes: &[u8]) -> usize {
+        let accepted = bytes.len().min(N - self.len);
+        let tail = (self.head + self.len) % N.max(1);
+
+        // The accepted bytes may wrap around the end of the buffer
+        let first = accepted.min(N - tail);
+        self.data[tail..tail + first].copy_from_slice(&bytes[..first]);
+        self.data[..accepted - first].copy_from_slice(&bytes[first..accepted]);
+
+        self.len += accepted;
+        accepted
+    }
+
+    /// Remove the oldest complete frame from the buffer, and return it.
+    ///
+    /// If the frame wraps around the end of the buffer, the buffer's contents are rotated so that
+    /// the frame can be returned as a contiguous slice. `None` is returned if there is no complete
+    /// frame in the buffer. Empty frames, caused by consecutive delimiters, are skipped.
+    pub fn pop_frame(&mut self) -> Option<&[u8]> {
+        // Drop the delimiters of empty frames
+        while self.len > 0 && self.data[self.head] == 0 {
+            self.head = (self.head + 1) % N;
+            self.len -= 1;
+        }
+
+        let frame_len = (0..self.len).find(|&i| self.data[(self.head + i) % N] == 0)?;
+
+        if self.head + frame_len > N {
+            self.data.rotate_left(self.head);
+            self.head = 0;
+        }
+
+        let start = self.head;
+        // Skip the frame and its terminator
+        self.head = (self.head + frame_len + 1) % N;
+        self.len -= frame_len + 1;
+        Some(&self.data[start..start + frame_len])
+    }
+}
+
+impl<const N: usize> Default for FrameRingBuffer<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DeviceId, Packet, Payload, Timestamp, TmPacket};
+
+    #[test]
+    fn pops_several_frames_from_one_chunk() {
+        let mut buffer = FrameRingBuffer::<16>::new();
+
+        buffer.extend_from_slice(&[1, 0, 2, 2, 0, 0, 3]);
+
+        assert_eq!(buffer.pop_frame(), Some(&[1][..]));
+        assert_eq!(buffer.pop_frame(), Some(&[2, 2][..]));
+        assert_eq!(buffer.pop_frame(), None);
+        assert_eq!(buffer.len(), 1);
+    }
+
+    #[test]
+    fn pops_frame_wrapping_around_end() {
+        let mut buffer = FrameRingBuffer::<6>::new();
+        buffer.extend_from_slice(&[1, 1, 1, 0]);
+        assert_eq!(buffer.pop_frame(), Some(&[1, 1, 1][..]));
+
+        assert_eq!(buffer.extend_from_slice(&[2, 3, 4]), 3);
+        assert_eq!(buffer.extend_from_slice(&[5, 0]), 2);
+
+        assert_eq!(buffer.pop_frame(), Some(&[2, 3, 4, 5][..]));
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn pops_frame_ending_at_end() {
+        let mut buffer = FrameRingBuffer::<4>::new();
+        buffer.extend_from_slice(&[1, 0]);
+        buffer.pop_frame();
+
+        buffer.extend_from_slice(&[2, 0, 3, 0]);
+
+        assert_eq!(buffer.pop_frame(), Some(&[2][..]));
+        assert_eq!(buffer.pop_frame(), Some(&[3][..]));
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn signals_overflow() {
+        let mut buffer = FrameRingBuffer::<4>::new();
+
+        assert_eq!(buffer.extend_from_slice(&[1, 2, 3, 4, 5]), 4);
+        assert_eq!(buffer.extend_from_slice(&[6]), 0);
+        assert_eq!(buffer.pop_frame(), None);
+
+        buffer.clear();
+        assert_eq!(buffer.extend_from_slice(&[6, 0]), 2);
+        assert_eq!(buffer.pop_frame(), Some(&[6][..]));
+    }
+
+    #[test]
+    fn decodes_packet_split_across_chunks() {
+        let packet = TmPacket::new(
+            DeviceId::Gps,
+            Timestamp::new(10).unwrap(),
+            Payload::from_raw_bytes([1, 2, 3]).unwrap(),
+        );
+        let mut encode_buffer = [0u8; TmPacket::MAX_ENCODE_BUFFER_SIZE];
+        let encoded = packet.encode(&mut encode_buffer).unwrap();
+        let mut buffer = FrameRingBuffer::<{ TmPacket::MAX_ENCODED_SIZE }>::new();
+
+        let (first, second) = encoded.split_at(5);
+        buffer.extend_from_slice(first);
+        assert_eq!(buffer.pop_frame(), None);
+        buffer.extend_from_slice(second);
+
+        let frame = buffer.pop_frame().unwrap();
+        assert_eq!(Packet::try_from(frame).unwrap(), Packet::TmPacket(packet));
+    }
+}
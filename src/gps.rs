@@ -0,0 +1,124 @@
+//! Canonical payload layout for [`DeviceId::Gps`](crate::DeviceId::Gps) packets.
+//!
+//! A GPS fix is serialized as the following little endian fields, in order:
+//! - 8 bytes for the latitude, in degrees (`f64`)
+//! - 8 bytes for the longitude, in degrees (`f64`)
+//! - 4 bytes for the altitude, in meters (`f32`)
+//! - 1 byte for the number of satellites in view (`u8`)
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::Payload;
+
+/// Error that can occur when reading a [`GpsFix`] from a payload
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum GpsError {
+    /// The payload doesn't have the size of a serialized fix
+    #[error("invalid gps payload length (expected {expected}, found {found})")]
+    InvalidLength { expected: usize, found: usize },
+}
+
+/// A position fix reported by the GPS device
+///
+/// # Example
+/// ```
+/// # use orbipacket::gps::GpsFix;
+/// let fix = GpsFix { lat: 38.7, lon: -9.3, alt: 120.5, sats: 7 };
+///
+/// let payload = fix.to_payload();
+///
+/// assert_eq!(payload.length(), GpsFix::SIZE);
+/// assert_eq!(GpsFix::from_payload(&payload)?, fix);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Copy, Clone, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct GpsFix {
+    /// Latitude, in degrees
+    pub lat: f64,
+    /// Longitude, in degrees
+    pub lon: f64,
+    /// Altitude, in meters
+    pub alt: f32,
+    /// Number of satellites in view
+    pub sats: u8,
+}
+
+impl GpsFix {
+    /// Size of a serialized fix, in bytes
+    pub const SIZE: usize = 8 + 8 + 4 + 1;
+
+    /// Serialize the fix into a payload.
+    pub fn to_payload(&self) -> Payload {
+        let mut bytes = [0u8; Self::SIZE];
+        bytes[0..8].copy_from_slice(&self.lat.to_le_bytes());
+        bytes[8..16].copy_from_slice(&self.lon.to_le_bytes());
+        bytes[16..20].copy_from_slice(&self.alt.to_le_bytes());
+        bytes[20] = self.sats;
+
+        // Unwrapping is safe here because a fix is much smaller than the maximum payload size
+        Payload::from_raw_bytes(bytes).unwrap()
+    }
+
+    /// Read a fix from a payload.
+    ///
+    /// # Errors
+    /// If the payload isn't exactly [`GpsFix::SIZE`] bytes long, an error variant is returned.
+    pub fn from_payload(payload: &Payload) -> Result<Self, GpsError> {
+        let bytes: &[u8; Self::SIZE] =
+            payload
+                .as_bytes()
+                .try_into()
+                .map_err(|_| GpsError::InvalidLength {
+                    expected: Self::SIZE,
+                    found: payload.length(),
+                })?;
+
+        // Unwrapping is safe here because the ranges have the size of each field
+        Ok(GpsFix {
+            lat: f64::from_le_bytes(bytes[0..8].try_into().unwrap()),
+            lon: f64::from_le_bytes(bytes[8..16].try_into().unwrap()),
+            alt: f32::from_le_bytes(bytes[16..20].try_into().unwrap()),
+            sats: bytes[20],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_payload_writes_fields_in_order() {
+        let fix = GpsFix {
+            lat: 1.0,
+            lon: 2.0,
+            alt: 3.0,
+            sats: 4,
+        };
+
+        let payload = fix.to_payload();
+
+        let bytes = payload.as_bytes();
+        assert_eq!(bytes[0..8], 1.0f64.to_le_bytes());
+        assert_eq!(bytes[8..16], 2.0f64.to_le_bytes());
+        assert_eq!(bytes[16..20], 3.0f32.to_le_bytes());
+        assert_eq!(bytes[20], 4);
+    }
+
+    #[test]
+    fn from_payload_rejects_invalid_length() {
+        let payload = Payload::from_raw_bytes([0u8; GpsFix::SIZE - 1]).unwrap();
+
+        assert_eq!(
+            GpsFix::from_payload(&payload),
+            Err(GpsError::InvalidLength {
+                expected: GpsFix::SIZE,
+                found: GpsFix::SIZE - 1
+            })
+        );
+    }
+}
@@ -4,7 +4,7 @@
 //! developed for communication with CanSat devices by the OrbiSat Oeiras team.
 //!
 //! This crate is `no_std` compatible, and can be used in embedded systems. It also doesn't perform any
-//! heap allocations.
+//! heap allocations, except in the modules gated behind the `alloc` feature (namely `ack`, `schema` and `text`).
 //!
 //! # Basics
 //! Packets come in two flavours, each represented by a struct:
@@ -17,12 +17,20 @@
 //! # Packet structure
 //! The packet structs closely follow the protocol's specification, which provides a full reference.
 //! A brief summary of the structs' fields is given below:
-//! - `version`: indicates the version of the protocol the packet adheres to
+//! - `version`: indicates the version of the protocol the packet adheres to, see [`ProtocolVersion`]
 //! - `payload_length`: length of the payload, in bytes
 //! - `device_id`: see [`DeviceId`]
+//! - `sequence`: optional counter used to detect lost packets, see [`TmPacket::with_sequence`]
 //! - `timestamp`: see [`Timestamp`]
 //! - `payload`: application specific data
 //!
+//! Each encoded packet also ends with a CRC-16/OPENSAFETY-B checksum of all other fields. The checksum is
+//! stored little-endian, unless the `crc-be` feature is enabled, in which case it is stored big-endian
+//! by both the encoder and the decoder. Deployments using a different CRC-16 algorithm can select it with
+//! the types in [`checksum`]. On reliable links, the checksum can be omitted entirely with
+//! [`encode::EncodeOptions::trusted_link`], as long as the decoder is configured to match with
+//! [`decode::DecodeOptions::trusted_link`].
+//!
 //! # Encoding
 //! Packets can be encoded into a buffer using any of [`TmPacket::encode`], [`TcPacket::encode`] or
 //! [`Packet::encode`]. All these methods accept a mutable byte slice to which they write the encoded
@@ -80,13 +88,23 @@
 //!
 //! # Decoding
 //! TODO: Decoding isn't implemented yet.
+//!
+//! # Fragmentation
+//! Data larger than [`Payload::MAX_SIZE`] can be split across several packets with
+//! [`fragment::fragment`], and put back together on the receiving side with a
+//! [`fragment::Reassembler`].
 
-static VERSION: u8 = 0x01;
+#[cfg(feature = "alloc")]
+extern crate alloc;
 
 pub mod payload;
 pub use payload::Payload;
 pub mod device_id;
 pub use device_id::DeviceId;
+pub mod sequence;
+pub use sequence::SequenceTracker;
+pub mod version;
+pub use version::ProtocolVersion;
 
 use core::fmt::Display;
 #[cfg(feature = "serde")]
@@ -99,6 +117,9 @@ pub enum TimestampError {
     /// The provided value is to large to be represented in 40 bits.
     #[error("value too large: {0}")]
     ValueTooLarge(u64),
+    /// The provided time is before the Unix epoch, and thus can't be represented.
+    #[error("time before the unix epoch")]
+    BeforeEpoch,
 }
 
 /// Time in microseconds since device startup
@@ -107,9 +128,23 @@ pub enum TimestampError {
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Timestamp(u64);
 
+/// Formats the timestamp as a raw number of microseconds, or in seconds with the alternate flag.
+///
+/// # Example
+/// ```
+/// # use orbipacket::Timestamp;
+/// let timestamp = Timestamp::new(12_345_678)?;
+/// assert_eq!(format!("{}", timestamp), "12345678 us");
+/// assert_eq!(format!("{:#}", timestamp), "12.345678 s");
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
 impl Display for Timestamp {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        write!(f, "{} us", self.0)
+        if f.alternate() {
+            write!(f, "{}.{:06} s", self.0 / 1_000_000, self.0 % 1_000_000)
+        } else {
+            write!(f, "{} us", self.0)
+        }
     }
 }
 
@@ -119,8 +154,8 @@ impl Timestamp {
     /// # Errors
     /// If the provided value is larger than 2^40 - 1, an error varian will be returned.
     /// This ensures that timestamps are only 40-bits long, as required by the protocol.
-    pub fn new(timestamp: u64) -> Result<Self, TimestampError> {
-        if timestamp >= 1 << 41 {
+    pub const fn new(timestamp: u64) -> Result<Self, TimestampError> {
+        if timestamp >= 1 << 40 {
             Err(TimestampError::ValueTooLarge(timestamp))
         } else {
             Ok(Timestamp(timestamp))
@@ -136,38 +171,206 @@ impl Timestamp {
     /// assert_eq!(timestamp.get(), 1234);
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
-    pub fn get(&self) -> u64 {
+    pub const fn get(&self) -> u64 {
         self.0
     }
+
+    /// Creates a new `Timestamp` from whole seconds and nanoseconds since device startup.
+    ///
+    /// As with [`core::time::Duration::new`], nanoseconds beyond a second carry over into the
+    /// seconds. Any sub-microsecond precision is truncated, since timestamps count microseconds.
+    ///
+    /// # Errors
+    /// An error variant is returned if the resulting number of microseconds doesn't fit in the
+    /// protocol's timestamp field (see [`Timestamp::new`]). If computing it overflows a `u64`,
+    /// the reported value saturates at `u64::MAX`.
+    ///
+    /// # Example
+    /// ```
+    /// # use orbipacket::Timestamp;
+    /// let timestamp = Timestamp::from_parts(12, 345_678_999)?;
+    /// assert_eq!(timestamp.get(), 12_345_678);
+    /// assert_eq!(timestamp.to_parts(), (12, 345_678_000));
+    ///
+    /// assert!(Timestamp::from_parts(u64::MAX, 0).is_err());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub const fn from_parts(secs: u64, nanos: u32) -> Result<Self, TimestampError> {
+        let micros = match secs.checked_mul(1_000_000) {
+            Some(micros) => micros.checked_add(nanos as u64 / 1_000),
+            None => None,
+        };
+        match micros {
+            Some(micros) => Self::new(micros),
+            None => Err(TimestampError::ValueTooLarge(u64::MAX)),
+        }
+    }
+
+    /// Returns the whole seconds and the remaining nanoseconds contained in this `Timestamp`.
+    ///
+    /// The nanoseconds are always less than a second, and a multiple of 1000.
+    pub const fn to_parts(&self) -> (u64, u32) {
+        // The remainder is less than a million, so the conversion to u32 is lossless
+        (self.0 / 1_000_000, (self.0 % 1_000_000) as u32 * 1_000)
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Timestamp {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        // Only generate values which fit in the protocol's 40-bit field
+        Ok(Timestamp(u.int_in_range(0..=(1 << 40) - 1)?))
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl Timestamp {
+    /// Converts the `Timestamp` into a date and time, interpreting it as the number of
+    /// microseconds since the Unix epoch.
+    ///
+    /// # Example
+    /// ```
+    /// # use orbipacket::Timestamp;
+    /// let timestamp = Timestamp::new(1_500_000)?;
+    /// assert_eq!(timestamp.to_datetime().timestamp_micros(), 1_500_000);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn to_datetime(&self) -> chrono::DateTime<chrono::Utc> {
+        // Unwrapping is safe here because a 40-bit number of microseconds is well within the
+        // range of DateTime
+        chrono::DateTime::from_timestamp_micros(self.0 as i64).unwrap()
+    }
+
+    /// Creates a new `Timestamp` from a date and time, as the number of microseconds since the
+    /// Unix epoch. Any sub-microsecond precision is truncated.
+    ///
+    /// # Errors
+    /// An error variant is returned if the date is before the Unix epoch, or if the number of
+    /// microseconds doesn't fit in the protocol's timestamp field (see [`Timestamp::new`]).
+    ///
+    /// # Example
+    /// ```
+    /// # use orbipacket::Timestamp;
+    /// let datetime = chrono::DateTime::from_timestamp_micros(1_500_000).unwrap();
+    /// assert_eq!(Timestamp::from_datetime(datetime)?.get(), 1_500_000);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn from_datetime(datetime: chrono::DateTime<chrono::Utc>) -> Result<Self, TimestampError> {
+        let micros =
+            u64::try_from(datetime.timestamp_micros()).map_err(|_| TimestampError::BeforeEpoch)?;
+        Self::new(micros)
+    }
 }
 
 /// A packet containing metadata and a payload
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "UncheckedPacket"))]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 struct InternalPacket {
-    version: u8,
+    version: ProtocolVersion,
     device_id: DeviceId,
+    sequence: Option<u16>,
     timestamp: Timestamp,
     payload: Payload,
+    /// The reserved low bits of the control byte, kept so that they round-trip
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "is_zero"))]
+    control_flags: u8,
+}
+
+/// Check if a value is zero, so that unset fields can be skipped when serializing
+#[cfg(feature = "serde")]
+fn is_zero(value: &u8) -> bool {
+    *value == 0
 }
 
 impl InternalPacket {
+    /// Mask of the reserved bits of the control byte
+    const CONTROL_FLAGS_MASK: u8 = 0b11;
+
     /// Create a new telemetry packet from the given packet fields
     fn new(device_id: DeviceId, timestamp: Timestamp, payload: Payload) -> Self {
         InternalPacket {
-            version: VERSION,
+            version: ProtocolVersion::V1,
             device_id,
+            sequence: None,
             timestamp,
             payload,
+            control_flags: 0,
+        }
+    }
+
+    /// Set the sequence number of the packet, bumping its protocol version
+    fn with_sequence(self, sequence: u16) -> Self {
+        InternalPacket {
+            version: ProtocolVersion::V2,
+            sequence: Some(sequence),
+            ..self
+        }
+    }
+}
+
+/// The fields of a deserialized packet, before checking they're consistent
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct UncheckedPacket {
+    version: ProtocolVersion,
+    device_id: DeviceId,
+    sequence: Option<u16>,
+    timestamp: Timestamp,
+    payload: Payload,
+    #[serde(default)]
+    control_flags: u8,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<UncheckedPacket> for InternalPacket {
+    type Error = &'static str;
+
+    fn try_from(packet: UncheckedPacket) -> Result<Self, Self::Error> {
+        if !packet.version.is_consistent() {
+            return Err("newer version with the number of a supported or invalid version");
+        }
+        match (packet.version, packet.sequence) {
+            (ProtocolVersion::V2, None) => return Err("missing sequence number for version 2"),
+            (ProtocolVersion::V1 | ProtocolVersion::Newer(_), Some(_)) => {
+                return Err("unexpected sequence number for version without one")
+            }
+            _ => {}
+        }
+        let timestamp = Timestamp::new(packet.timestamp.get())
+            .map_err(|_| "timestamp too large to be represented in 40 bits")?;
+        if packet.control_flags & !Self::CONTROL_FLAGS_MASK != 0 {
+            return Err("control flags wider than the reserved bits of the control byte");
         }
+
+        Ok(InternalPacket {
+            version: packet.version,
+            device_id: packet.device_id,
+            sequence: packet.sequence,
+            timestamp,
+            payload: packet.payload,
+            control_flags: packet.control_flags,
+        })
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for InternalPacket {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let packet = InternalPacket::new(u.arbitrary()?, u.arbitrary()?, u.arbitrary()?);
+        // Going through with_sequence keeps the version consistent with the header layout
+        Ok(match u.arbitrary()? {
+            Some(sequence) => packet.with_sequence(sequence),
+            None => packet,
+        })
     }
 }
 
 /// # Packet field getters
 impl InternalPacket {
     /// The protocol version the packet adheres to
-    fn version(&self) -> u8 {
+    fn version(&self) -> ProtocolVersion {
         self.version
     }
 
@@ -184,6 +387,16 @@ impl InternalPacket {
         &self.device_id
     }
 
+    /// The sequence number of the packet, if it has one
+    fn sequence(&self) -> Option<u16> {
+        self.sequence
+    }
+
+    /// The reserved low bits of the control byte
+    fn control_flags(&self) -> u8 {
+        self.control_flags
+    }
+
     /// The time at which the packet was created
     ///
     /// # Example
@@ -211,6 +424,19 @@ impl InternalPacket {
     }
 }
 
+/// # Packet field setters
+impl InternalPacket {
+    /// Mutable access to the contents of the packet
+    fn payload_mut(&mut self) -> &mut Payload {
+        &mut self.payload
+    }
+
+    /// Replace the contents of the packet
+    fn set_payload(&mut self, payload: Payload) {
+        self.payload = payload;
+    }
+}
+
 /// # Packet size
 impl InternalPacket {
     /// Number of bytes introduced by packet metadata
@@ -221,23 +447,49 @@ impl InternalPacket {
     /// - 1 byte for the device ID and packet kind
     /// - 5 bytes for the timestamp
     /// - 2 bytes for the CRC
-    const OVERHEAD: usize = 1 + 1 + 1 + 5 + 2;
+    const OVERHEAD: usize = Self::HEADER_SIZE + Self::CRC_SIZE;
+
+    /// Number of bytes taken by the header fields preceding the payload, except the sequence
+    /// number
+    const HEADER_SIZE: usize = 1 + 1 + 1 + 5;
+
+    /// Number of bytes introduced by the sequence number, when present
+    const SEQUENCE_SIZE: usize = 2;
+
+    /// Number of bytes taken by the CRC, which is omitted in trusted link mode
+    const CRC_SIZE: usize = 2;
 
     /// Maximum size of an unstuffed packet in bytes
     ///
-    /// Unstuffed packets contain only static overhead and the payload, thus:
+    /// Unstuffed packets contain only static overhead, the optional sequence number and the
+    /// payload, thus:
     /// ```
     /// # use orbipacket::{TmPacket, Payload};
-    /// assert_eq!(TmPacket::MAX_SIZE, TmPacket::OVERHEAD + Payload::MAX_SIZE);
+    /// assert_eq!(TmPacket::MAX_SIZE, TmPacket::OVERHEAD + TmPacket::SEQUENCE_SIZE + Payload::MAX_SIZE);
     /// ```
-    const MAX_SIZE: usize = Self::OVERHEAD + Payload::MAX_SIZE;
+    const MAX_SIZE: usize = Self::OVERHEAD + Self::SEQUENCE_SIZE + Payload::MAX_SIZE;
 
     /// Maximum size of an encoded packet, in bytes
     const MAX_ENCODED_SIZE: usize = cobs::max_encoding_length(Self::MAX_SIZE) + 1;
 
+    /// Minimum size of an unstuffed packet in bytes, that of a packet with an empty payload
+    const MIN_SIZE: usize = Self::OVERHEAD;
+
+    /// Minimum size of an encoded packet, in bytes
+    const MIN_ENCODED_SIZE: usize = cobs::max_encoding_length(Self::MIN_SIZE) + 1;
+
     /// Size of the packet, unstuffed, in bytes
     fn size(&self) -> usize {
-        Self::OVERHEAD + self.payload.length()
+        Self::OVERHEAD + self.sequence_size() + self.payload.length()
+    }
+
+    /// Number of bytes taken by the sequence number in this packet
+    fn sequence_size(&self) -> usize {
+        if self.sequence.is_some() {
+            Self::SEQUENCE_SIZE
+        } else {
+            0
+        }
     }
 
     /// Size of the packet, after stuffing, in bytes, including the termination byte
@@ -246,6 +498,66 @@ impl InternalPacket {
     }
 }
 
+/// Generate constructors building a packet around a single value, encoded as a little-endian
+/// payload.
+macro_rules! typed_constructors {
+    ($packet:ident, $($method:ident => $from:ident($ty:ty)),* $(,)?) => {
+        /// # Typed payload constructors
+        impl $packet {
+            $(
+                #[doc = concat!(
+                    "Create a new packet whose payload is the given `", stringify!($ty),
+                    "`, in little-endian order"
+                )]
+                pub fn $method(device_id: DeviceId, timestamp: Timestamp, value: $ty) -> Self {
+                    Self::new(device_id, timestamp, Payload::$from(value))
+                }
+            )*
+
+            /// Create a new packet whose payload is a copy of the given bytes
+            ///
+            /// # Errors
+            /// Returns [`PayloadError::PayloadTooLong`](payload::PayloadError::PayloadTooLong) if
+            /// there are more bytes than fit in a payload.
+            pub fn with_bytes(
+                device_id: DeviceId,
+                timestamp: Timestamp,
+                bytes: &[u8],
+            ) -> Result<Self, payload::PayloadError> {
+                Ok(Self::new(device_id, timestamp, Payload::from_raw_bytes(bytes)?))
+            }
+        }
+    };
+}
+
+typed_constructors!(
+    TmPacket,
+    with_u8 => from_u8(u8),
+    with_i8 => from_i8(i8),
+    with_u16 => from_u16(u16),
+    with_i16 => from_i16(i16),
+    with_u32 => from_u32(u32),
+    with_i32 => from_i32(i32),
+    with_u64 => from_u64(u64),
+    with_i64 => from_i64(i64),
+    with_f32 => from_f32(f32),
+    with_f64 => from_f64(f64),
+);
+
+typed_constructors!(
+    TcPacket,
+    with_u8 => from_u8(u8),
+    with_i8 => from_i8(i8),
+    with_u16 => from_u16(u16),
+    with_i16 => from_i16(i16),
+    with_u32 => from_u32(u32),
+    with_i32 => from_i32(i32),
+    with_u64 => from_u64(u64),
+    with_i64 => from_i64(i64),
+    with_f32 => from_f32(f32),
+    with_f64 => from_f64(f64),
+);
+
 /// A telemetry packet
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -257,12 +569,28 @@ impl TmPacket {
     pub fn new(device_id: DeviceId, timestamp: Timestamp, payload: Payload) -> Self {
         TmPacket(InternalPacket::new(device_id, timestamp, payload))
     }
+
+    /// Attach a sequence number to the packet.
+    ///
+    /// Packets with a sequence number use a newer protocol version, since the number is carried in
+    /// the packet header. Receivers can track it with a [`SequenceTracker`] to detect lost packets.
+    ///
+    /// # Example
+    /// ```
+    /// # use orbipacket::{TmPacket, DeviceId, Timestamp, Payload};
+    /// let packet = TmPacket::new(DeviceId::System, Timestamp::new(0)?, Payload::new()).with_sequence(7);
+    /// assert_eq!(packet.sequence(), Some(7));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_sequence(self, sequence: u16) -> Self {
+        TmPacket(self.0.with_sequence(sequence))
+    }
 }
 
 /// # Packet field getters
 impl TmPacket {
     /// The protocol version the packet adheres to
-    pub fn version(&self) -> u8 {
+    pub fn version(&self) -> ProtocolVersion {
         self.0.version()
     }
 
@@ -279,6 +607,20 @@ impl TmPacket {
         self.0.device_id()
     }
 
+    /// The sequence number of the packet, if it has one
+    pub fn sequence(&self) -> Option<u16> {
+        self.0.sequence()
+    }
+
+    /// The reserved low bits of the control byte, as received
+    ///
+    /// The current protocol doesn't define these bits, so they're 0 unless the packet was
+    /// decoded with [`DecodeOptions::allow_reserved_bits`](decode::DecodeOptions::allow_reserved_bits)
+    /// from a frame setting them. They're written back when the packet is encoded.
+    pub fn control_flags(&self) -> u8 {
+        self.0.control_flags()
+    }
+
     /// The time at which the packet was created
     ///
     /// # Example
@@ -306,6 +648,39 @@ impl TmPacket {
     }
 }
 
+/// # Packet field setters
+impl TmPacket {
+    /// Mutable access to the contents of the packet
+    ///
+    /// The CRC is computed when the packet is encoded, so the payload can be freely modified.
+    ///
+    /// # Example
+    /// ```
+    /// # use orbipacket::{TmPacket, DeviceId, Timestamp, Payload};
+    /// let mut packet = TmPacket::new(DeviceId::System, Timestamp::new(0)?, Payload::new());
+    /// *packet.payload_mut() = Payload::from_u8(7);
+    /// assert_eq!(*packet.payload(), Payload::from_u8(7));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn payload_mut(&mut self) -> &mut Payload {
+        self.0.payload_mut()
+    }
+
+    /// Replace the contents of the packet
+    ///
+    /// # Example
+    /// ```
+    /// # use orbipacket::{TmPacket, DeviceId, Timestamp, Payload};
+    /// let mut packet = TmPacket::new(DeviceId::System, Timestamp::new(0)?, Payload::new());
+    /// packet.set_payload(Payload::from_u8(7));
+    /// assert_eq!(*packet.payload(), Payload::from_u8(7));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn set_payload(&mut self, payload: Payload) {
+        self.0.set_payload(payload)
+    }
+}
+
 /// # Packet size
 impl TmPacket {
     /// Number of bytes introduced by packet metadata
@@ -318,18 +693,35 @@ impl TmPacket {
     /// - 2 bytes for the CRC
     pub const OVERHEAD: usize = InternalPacket::OVERHEAD;
 
+    /// Number of bytes introduced by the sequence number, when present
+    pub const SEQUENCE_SIZE: usize = InternalPacket::SEQUENCE_SIZE;
+
+    /// Number of bytes taken by the CRC, which is omitted in trusted link mode
+    pub const CRC_SIZE: usize = InternalPacket::CRC_SIZE;
+
     /// Maximum size of an unstuffed packet in bytes
     ///
-    /// Unstuffed packets contain only static overhead and the payload, thus:
+    /// Unstuffed packets contain only static overhead, the optional sequence number and the
+    /// payload, thus:
     /// ```
     /// # use orbipacket::{TmPacket, Payload};
-    /// assert_eq!(TmPacket::MAX_SIZE, TmPacket::OVERHEAD + Payload::MAX_SIZE);
+    /// assert_eq!(TmPacket::MAX_SIZE, TmPacket::OVERHEAD + TmPacket::SEQUENCE_SIZE + Payload::MAX_SIZE);
     /// ```
     pub const MAX_SIZE: usize = InternalPacket::MAX_SIZE;
 
     /// Maximum size of a stuffed packet, in bytes, including the termination byte
     pub const MAX_ENCODED_SIZE: usize = InternalPacket::MAX_ENCODED_SIZE;
 
+    /// Minimum size of an unstuffed packet in bytes, that of a packet with an empty payload and
+    /// no sequence number
+    pub const MIN_SIZE: usize = InternalPacket::MIN_SIZE;
+
+    /// Minimum size of a stuffed packet, in bytes, including the termination byte
+    ///
+    /// This is the size of the smallest buffer able to hold an encoded packet, when packets are
+    /// encoded with a CRC.
+    pub const MIN_ENCODED_SIZE: usize = InternalPacket::MIN_ENCODED_SIZE;
+
     /// Size of the packet, unstuffed, in bytes
     pub fn size(&self) -> usize {
         self.0.size()
@@ -341,6 +733,13 @@ impl TmPacket {
     }
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for TmPacket {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(TmPacket(u.arbitrary()?))
+    }
+}
+
 impl Display for TmPacket {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(
@@ -363,12 +762,28 @@ impl TcPacket {
     pub fn new(device_id: DeviceId, timestamp: Timestamp, payload: Payload) -> Self {
         TcPacket(InternalPacket::new(device_id, timestamp, payload))
     }
+
+    /// Attach a sequence number to the packet.
+    ///
+    /// Packets with a sequence number use a newer protocol version, since the number is carried in
+    /// the packet header. Receivers can track it with a [`SequenceTracker`] to detect lost packets.
+    ///
+    /// # Example
+    /// ```
+    /// # use orbipacket::{TcPacket, DeviceId, Timestamp, Payload};
+    /// let packet = TcPacket::new(DeviceId::System, Timestamp::new(0)?, Payload::new()).with_sequence(7);
+    /// assert_eq!(packet.sequence(), Some(7));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_sequence(self, sequence: u16) -> Self {
+        TcPacket(self.0.with_sequence(sequence))
+    }
 }
 
 /// # Packet field getters
 impl TcPacket {
     /// The protocol version the packet adheres to
-    pub fn version(&self) -> u8 {
+    pub fn version(&self) -> ProtocolVersion {
         self.0.version()
     }
 
@@ -398,6 +813,20 @@ impl TcPacket {
         self.0.device_id()
     }
 
+    /// The sequence number of the packet, if it has one
+    pub fn sequence(&self) -> Option<u16> {
+        self.0.sequence()
+    }
+
+    /// The reserved low bits of the control byte, as received
+    ///
+    /// The current protocol doesn't define these bits, so they're 0 unless the packet was
+    /// decoded with [`DecodeOptions::allow_reserved_bits`](decode::DecodeOptions::allow_reserved_bits)
+    /// from a frame setting them. They're written back when the packet is encoded.
+    pub fn control_flags(&self) -> u8 {
+        self.0.control_flags()
+    }
+
     /// The contents of the packet
     ///
     /// # Example
@@ -412,6 +841,39 @@ impl TcPacket {
     }
 }
 
+/// # Packet field setters
+impl TcPacket {
+    /// Mutable access to the contents of the packet
+    ///
+    /// The CRC is computed when the packet is encoded, so the payload can be freely modified.
+    ///
+    /// # Example
+    /// ```
+    /// # use orbipacket::{TcPacket, DeviceId, Timestamp, Payload};
+    /// let mut packet = TcPacket::new(DeviceId::System, Timestamp::new(0)?, Payload::new());
+    /// *packet.payload_mut() = Payload::from_u8(7);
+    /// assert_eq!(*packet.payload(), Payload::from_u8(7));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn payload_mut(&mut self) -> &mut Payload {
+        self.0.payload_mut()
+    }
+
+    /// Replace the contents of the packet
+    ///
+    /// # Example
+    /// ```
+    /// # use orbipacket::{TcPacket, DeviceId, Timestamp, Payload};
+    /// let mut packet = TcPacket::new(DeviceId::System, Timestamp::new(0)?, Payload::new());
+    /// packet.set_payload(Payload::from_u8(7));
+    /// assert_eq!(*packet.payload(), Payload::from_u8(7));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn set_payload(&mut self, payload: Payload) {
+        self.0.set_payload(payload)
+    }
+}
+
 /// # Packet size
 impl TcPacket {
     /// Number of bytes introduced by packet metadata
@@ -424,18 +886,35 @@ impl TcPacket {
     /// - 2 bytes for the CRC
     pub const OVERHEAD: usize = InternalPacket::OVERHEAD;
 
+    /// Number of bytes introduced by the sequence number, when present
+    pub const SEQUENCE_SIZE: usize = InternalPacket::SEQUENCE_SIZE;
+
+    /// Number of bytes taken by the CRC, which is omitted in trusted link mode
+    pub const CRC_SIZE: usize = InternalPacket::CRC_SIZE;
+
     /// Maximum size of an unstuffed packet in bytes
     ///
-    /// Unstuffed packets contain only static overhead and the payload, thus:
+    /// Unstuffed packets contain only static overhead, the optional sequence number and the
+    /// payload, thus:
     /// ```
     /// # use orbipacket::{TcPacket, Payload};
-    /// assert_eq!(TcPacket::MAX_SIZE, TcPacket::OVERHEAD + Payload::MAX_SIZE);
+    /// assert_eq!(TcPacket::MAX_SIZE, TcPacket::OVERHEAD + TcPacket::SEQUENCE_SIZE + Payload::MAX_SIZE);
     /// ```
     pub const MAX_SIZE: usize = InternalPacket::MAX_SIZE;
 
     /// Maximum size of a stuffed packet, in bytes, including the termination byte
     pub const MAX_ENCODED_SIZE: usize = InternalPacket::MAX_ENCODED_SIZE;
 
+    /// Minimum size of an unstuffed packet in bytes, that of a packet with an empty payload and
+    /// no sequence number
+    pub const MIN_SIZE: usize = InternalPacket::MIN_SIZE;
+
+    /// Minimum size of a stuffed packet, in bytes, including the termination byte
+    ///
+    /// This is the size of the smallest buffer able to hold an encoded packet, when packets are
+    /// encoded with a CRC.
+    pub const MIN_ENCODED_SIZE: usize = InternalPacket::MIN_ENCODED_SIZE;
+
     /// Size of the packet, unstuffed, in bytes
     pub fn size(&self) -> usize {
         self.0.size()
@@ -447,6 +926,13 @@ impl TcPacket {
     }
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for TcPacket {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(TcPacket(u.arbitrary()?))
+    }
+}
+
 impl Display for TcPacket {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(
@@ -459,14 +945,115 @@ impl Display for TcPacket {
 }
 
 /// An arbitrary packet
+///
+/// # Serialization
+/// With the `serde` feature, packets are serialized as a flat map of their fields, with a `kind`
+/// field set to `"tm"` or `"tc"`, e.g. in JSON:
+/// ```json
+/// {"kind":"tm","version":"V1","device_id":"Gps","sequence":null,"timestamp":10,"payload":"07"}
+/// ```
+/// Deserialization checks that the fields are consistent, e.g. that the sequence number is only
+/// present for versions which have one.
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Packet {
     TmPacket(TmPacket),
     TcPacket(TcPacket),
 }
 
+// Packets are (de)serialized through flat structs, rather than with `#[serde(tag = "kind")]`,
+// since deriving internally tagged enums requires buffering content, which needs an allocator
+
+/// The value of the `kind` field of a serialized packet
+#[cfg(feature = "serde")]
+#[derive(Copy, Clone, Serialize, Deserialize)]
+enum PacketTag {
+    #[serde(rename = "tm")]
+    Tm,
+    #[serde(rename = "tc")]
+    Tc,
+}
+
+/// The fields of a packet being serialized, along with its kind
+#[cfg(feature = "serde")]
+#[derive(Serialize)]
+struct TaggedPacketRef<'a> {
+    kind: PacketTag,
+    version: ProtocolVersion,
+    device_id: DeviceId,
+    sequence: Option<u16>,
+    timestamp: Timestamp,
+    payload: &'a Payload,
+    #[serde(skip_serializing_if = "is_zero")]
+    control_flags: u8,
+}
+
+/// The fields of a deserialized packet, along with its kind, before checking they're consistent
+#[cfg(feature = "serde")]
+#[derive(Deserialize)]
+struct UncheckedTaggedPacket {
+    kind: PacketTag,
+    version: ProtocolVersion,
+    device_id: DeviceId,
+    sequence: Option<u16>,
+    timestamp: Timestamp,
+    payload: Payload,
+    #[serde(default)]
+    control_flags: u8,
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for Packet {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let (kind, packet) = match self {
+            Packet::TmPacket(packet) => (PacketTag::Tm, &packet.0),
+            Packet::TcPacket(packet) => (PacketTag::Tc, &packet.0),
+        };
+        TaggedPacketRef {
+            kind,
+            version: packet.version,
+            device_id: packet.device_id,
+            sequence: packet.sequence,
+            timestamp: packet.timestamp,
+            payload: &packet.payload,
+            control_flags: packet.control_flags,
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Packet {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let tagged = UncheckedTaggedPacket::deserialize(deserializer)?;
+        let packet = InternalPacket::try_from(UncheckedPacket {
+            version: tagged.version,
+            device_id: tagged.device_id,
+            sequence: tagged.sequence,
+            timestamp: tagged.timestamp,
+            payload: tagged.payload,
+            control_flags: tagged.control_flags,
+        })
+        .map_err(serde::de::Error::custom)?;
+
+        Ok(match tagged.kind {
+            PacketTag::Tm => Packet::TmPacket(TmPacket(packet)),
+            PacketTag::Tc => Packet::TcPacket(TcPacket(packet)),
+        })
+    }
+}
+
+/// The kind of a [`Packet`], as recorded in the top bit of its control byte
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum PacketKind {
+    /// A telemetry packet, see [`TmPacket`]
+    Telemetry,
+    /// A telecommand packet, see [`TcPacket`]
+    Telecommand,
+}
+
 impl Packet {
     /// Returns `true` if the packet is a [TmPacket]
     ///
@@ -499,10 +1086,211 @@ impl Packet {
     pub fn is_tc_packet(&self) -> bool {
         matches!(self, Packet::TcPacket(_))
     }
+
+    /// The kind of the packet
+    pub fn kind(&self) -> PacketKind {
+        match self {
+            Packet::TmPacket(_) => PacketKind::Telemetry,
+            Packet::TcPacket(_) => PacketKind::Telecommand,
+        }
+    }
+
+    /// The reserved low bits of the control byte, as received
+    ///
+    /// See [`TmPacket::control_flags`] for details.
+    ///
+    /// # Example
+    /// ```
+    /// use orbipacket::{decode::DecodeOptions, Packet, TmPacket, DeviceId, Timestamp, Payload};
+    ///
+    /// let packet = Packet::TmPacket(TmPacket::new(DeviceId::Gps, Timestamp::new(0)?, Payload::new()));
+    /// assert_eq!(packet.control_flags(), 0);
+    ///
+    /// // A frame from a future sender, setting the lowest bit of the control byte
+    /// let mut frame = [0x02, 0x01, 0x03, 0x09, 0x0a, 0x01, 0x01, 0x01, 0x03, 0x9e, 0x81, 0x00];
+    /// # #[cfg(feature = "crc-be")]
+    /// # frame.swap(9, 10);
+    /// let options = DecodeOptions::new().allow_reserved_bits(true);
+    /// let packet = Packet::decode_single_with_options(&mut frame, &options)?;
+    /// assert_eq!(packet.control_flags(), 0b01);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn control_flags(&self) -> u8 {
+        match self {
+            Packet::TmPacket(packet) => packet.control_flags(),
+            Packet::TcPacket(packet) => packet.control_flags(),
+        }
+    }
+}
+
+/// # Packet field setters
+impl Packet {
+    /// Mutable access to the contents of the packet
+    ///
+    /// # Example
+    /// ```
+    /// # use orbipacket::{Packet, TmPacket, DeviceId, Timestamp, Payload};
+    /// let mut packet = Packet::TmPacket(TmPacket::new(DeviceId::System, Timestamp::new(0)?, Payload::new()));
+    /// *packet.payload_mut() = Payload::from_u8(7);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn payload_mut(&mut self) -> &mut Payload {
+        match self {
+            Packet::TmPacket(packet) => packet.payload_mut(),
+            Packet::TcPacket(packet) => packet.payload_mut(),
+        }
+    }
+
+    /// Replace the contents of the packet
+    pub fn set_payload(&mut self, payload: Payload) {
+        match self {
+            Packet::TmPacket(packet) => packet.set_payload(payload),
+            Packet::TcPacket(packet) => packet.set_payload(payload),
+        }
+    }
+
+    /// Convert the packet into the given kind, keeping all its other fields.
+    ///
+    /// # Example
+    /// ```
+    /// # use orbipacket::{Packet, PacketKind, TcPacket, DeviceId, Timestamp, Payload};
+    /// let command = Packet::TcPacket(TcPacket::new(DeviceId::Gps, Timestamp::new(10)?, Payload::from_u8(7)));
+    ///
+    /// let echo = command.with_kind(PacketKind::Telemetry);
+    ///
+    /// assert_eq!(echo.kind(), PacketKind::Telemetry);
+    /// assert_eq!(echo.with_kind(PacketKind::Telecommand), command);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_kind(self, kind: PacketKind) -> Self {
+        let packet = match self {
+            Packet::TmPacket(TmPacket(packet)) | Packet::TcPacket(TcPacket(packet)) => packet,
+        };
+        match kind {
+            PacketKind::Telemetry => Packet::TmPacket(TmPacket(packet)),
+            PacketKind::Telecommand => Packet::TcPacket(TcPacket(packet)),
+        }
+    }
+
+    /// Convert the packet into a telemetry packet, keeping all its other fields.
+    pub fn into_tm(self) -> Self {
+        self.with_kind(PacketKind::Telemetry)
+    }
+
+    /// Convert the packet into a telecommand packet, keeping all its other fields.
+    pub fn into_tc(self) -> Self {
+        self.with_kind(PacketKind::Telecommand)
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Packet {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(if u.arbitrary()? {
+            Packet::TmPacket(u.arbitrary()?)
+        } else {
+            Packet::TcPacket(u.arbitrary()?)
+        })
+    }
+}
+
+/// # Packet size
+impl Packet {
+    /// Size of the packet, unstuffed, in bytes
+    pub fn size(&self) -> usize {
+        match self {
+            Packet::TmPacket(packet) => packet.size(),
+            Packet::TcPacket(packet) => packet.size(),
+        }
+    }
+
+    /// Size of the packet, after stuffing, in bytes, including the termination byte
+    pub fn encoded_size(&self) -> usize {
+        match self {
+            Packet::TmPacket(packet) => packet.encoded_size(),
+            Packet::TcPacket(packet) => packet.encoded_size(),
+        }
+    }
+}
+
+/// # Packet identity
+impl Packet {
+    /// A stable identifier of the packet's contents, for deduplication and cross-referencing of
+    /// logs.
+    ///
+    /// This is a 64-bit FNV-1a hash of the packet's version, kind, device ID, sequence number
+    /// (if any), timestamp, payload and control flags (if any are set). Two packets with the same contents always have the same
+    /// ID, regardless of the platform or run, unlike the value produced by the [`Hash`]
+    /// implementation. The CRC isn't included, since it concerns transport rather than identity.
+    ///
+    /// Being a non-cryptographic hash, different packets may collide, although that's unlikely.
+    ///
+    /// # Example
+    /// ```
+    /// # use orbipacket::{Packet, TmPacket, TcPacket, DeviceId, Timestamp, Payload};
+    /// let tm = TmPacket::new(DeviceId::Gps, Timestamp::new(10)?, Payload::from_u8(1));
+    /// let tc = TcPacket::new(DeviceId::Gps, Timestamp::new(10)?, Payload::from_u8(1));
+    ///
+    /// assert_eq!(Packet::TmPacket(tm).content_id(), Packet::TmPacket(tm).content_id());
+    /// assert_ne!(Packet::TmPacket(tm).content_id(), Packet::TcPacket(tc).content_id());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn content_id(&self) -> u64 {
+        const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+        const FNV_PRIME: u64 = 0x100000001b3;
+
+        let (packet, kind) = match self {
+            Packet::TmPacket(packet) => (&packet.0, 0),
+            Packet::TcPacket(packet) => (&packet.0, 1),
+        };
+        let sequence = packet.sequence.map(u16::to_le_bytes);
+        let timestamp = packet.timestamp.get().to_le_bytes();
+        // The version determines whether a sequence number is present, and the length where the
+        // payload ends, so the hashed bytes are unambiguous
+        let fields = [packet.version.as_u8(), kind, packet.device_id.as_u8()];
+        let length = [packet.payload.length() as u8];
+        // Flags are only hashed when set, so that the IDs of packets without them are unaffected.
+        // The length marks the end of the payload, so a trailing byte is still unambiguous.
+        let flags = [packet.control_flags];
+        let flags = if packet.control_flags != 0 {
+            &flags[..]
+        } else {
+            &[][..]
+        };
+
+        [
+            &fields[..],
+            sequence.as_ref().map_or(&[][..], |sequence| &sequence[..]),
+            &timestamp[..5],
+            &length[..],
+            packet.payload.as_bytes(),
+            flags,
+        ]
+        .into_iter()
+        .flatten()
+        .fold(FNV_OFFSET_BASIS, |hash, &byte| {
+            (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+        })
+    }
 }
 
+pub mod accel;
+#[cfg(feature = "alloc")]
+pub mod ack;
+pub mod checksum;
+pub mod command;
+#[cfg(feature = "bit-correction")]
+pub mod correct;
 pub mod decode;
 pub mod encode;
+pub mod fragment;
+pub mod framer;
+pub mod gps;
+pub mod relay;
+#[cfg(feature = "alloc")]
+pub mod schema;
+#[cfg(feature = "base64")]
+pub mod text;
 
 #[cfg(test)]
 mod tests {
@@ -518,11 +1306,95 @@ mod tests {
         assert_eq!(timestamp.get(), 1234);
     }
 
+    #[test]
+    fn typed_constructors_build_little_endian_payloads() {
+        let timestamp = Timestamp(5);
+        assert_eq!(
+            TmPacket::with_u16(DeviceId::Gps, timestamp, 0x1234),
+            TmPacket::new(
+                DeviceId::Gps,
+                timestamp,
+                Payload::from_raw_bytes([0x34, 0x12]).unwrap()
+            )
+        );
+        assert_eq!(
+            *TcPacket::with_f32(DeviceId::Gps, timestamp, 1.5).payload(),
+            1.5f32.to_le_bytes()[..]
+        );
+        assert_eq!(
+            TmPacket::with_bytes(DeviceId::Gps, timestamp, &[1, 2, 3]).unwrap(),
+            TmPacket::new(
+                DeviceId::Gps,
+                timestamp,
+                Payload::from_raw_bytes([1, 2, 3]).unwrap()
+            )
+        );
+        assert!(matches!(
+            TcPacket::with_bytes(DeviceId::Gps, timestamp, &[0; Payload::MAX_SIZE + 1]),
+            Err(payload::PayloadError::PayloadTooLong(_))
+        ));
+    }
+
+    #[test]
+    fn timestamp_from_parts_checks_range() {
+        assert_eq!(Timestamp::from_parts(0, 999).unwrap(), Timestamp(0));
+        assert_eq!(
+            Timestamp::from_parts(1, 2_000_000_000).unwrap(),
+            Timestamp(3_000_000)
+        );
+
+        let max = Timestamp((1 << 40) - 1);
+        let (secs, nanos) = max.to_parts();
+        assert_eq!(Timestamp::from_parts(secs, nanos).unwrap(), max);
+        assert!(matches!(
+            Timestamp::from_parts(secs, nanos + 1_000),
+            Err(TimestampError::ValueTooLarge(value)) if value == 1 << 40
+        ));
+        assert!(matches!(
+            Timestamp::from_parts(u64::MAX / 1_000, 0),
+            Err(TimestampError::ValueTooLarge(u64::MAX))
+        ));
+    }
+
+    #[test]
+    fn min_sizes_match_empty_packet() {
+        let packet = TmPacket::new(DeviceId::System, Timestamp(0), Payload::new());
+        let mut buffer = [0u8; TmPacket::MAX_ENCODE_BUFFER_SIZE];
+
+        assert_eq!(TmPacket::MIN_SIZE, TmPacket::OVERHEAD);
+        assert_eq!(packet.size(), TmPacket::MIN_SIZE);
+        assert_eq!(
+            packet.encode(&mut buffer).unwrap().len(),
+            TmPacket::MIN_ENCODED_SIZE
+        );
+        assert_eq!(TcPacket::MIN_ENCODED_SIZE, TmPacket::MIN_ENCODED_SIZE);
+    }
+
+    #[test]
+    fn timestamp_new_rejects_values_wider_than_40_bits() {
+        assert!(Timestamp::new((1 << 40) - 1).is_ok());
+        assert!(matches!(
+            Timestamp::new(1 << 40),
+            Err(TimestampError::ValueTooLarge(value)) if value == 1 << 40
+        ));
+    }
+
+    #[test]
+    fn timestamp_alternate_display_is_in_seconds() {
+        assert_eq!(format!("{:#}", Timestamp(0)), "0.000000 s");
+        assert_eq!(format!("{:#}", Timestamp(1_000_042)), "1.000042 s");
+        assert_eq!(
+            format!("{:#}", Timestamp((1 << 40) - 1)),
+            "1099511.627775 s"
+        );
+        assert_eq!(format!("{}", Timestamp(1_000_042)), "1000042 us");
+    }
+
     #[test]
     fn tm_packet_getters_return_values_from_constructor() {
         let payload = payload(3u8);
         let tm_packet = TmPacket::new(DeviceId::System, Timestamp(0), payload);
-        assert_eq!(tm_packet.version(), VERSION);
+        assert_eq!(tm_packet.version(), ProtocolVersion::V1);
         assert_eq!(tm_packet.device_id(), &DeviceId::System);
         assert_eq!(tm_packet.timestamp().0, 0);
         assert_eq!(*tm_packet.payload(), payload);
@@ -535,14 +1407,28 @@ mod tests {
 
     #[test]
     fn tm_packet_size_returns_size_of_packet() {
-        assert_eq!(TmPacket::MAX_ENCODED_SIZE, 10 + 2 + 256);
+        assert_eq!(TmPacket::MAX_ENCODED_SIZE, 10 + 2 + 2 + 256);
+    }
+
+    #[test]
+    fn tm_packet_with_sequence_bumps_version() {
+        let tm_packet = TmPacket::new(DeviceId::System, Timestamp(0), payload(3u8));
+        assert_eq!(tm_packet.sequence(), None);
+
+        let tm_packet = tm_packet.with_sequence(0x1234);
+        assert_eq!(tm_packet.version(), ProtocolVersion::V2);
+        assert_eq!(tm_packet.sequence(), Some(0x1234));
+        assert_eq!(
+            tm_packet.size(),
+            TmPacket::OVERHEAD + TmPacket::SEQUENCE_SIZE + 1
+        );
     }
 
     #[test]
     fn tc_packet_getters_return_values_from_constructor() {
         let payload = payload(3u8);
         let tc_packet = TcPacket::new(DeviceId::System, Timestamp(0), payload);
-        assert_eq!(tc_packet.version(), VERSION);
+        assert_eq!(tc_packet.version(), ProtocolVersion::V1);
         assert_eq!(tc_packet.device_id(), &DeviceId::System);
         assert_eq!(tc_packet.timestamp().0, 0);
         assert_eq!(*tc_packet.payload(), payload);
@@ -555,7 +1441,21 @@ mod tests {
 
     #[test]
     fn tc_packet_size_returns_size_of_packet() {
-        assert_eq!(TcPacket::MAX_ENCODED_SIZE, 12 + 256);
+        assert_eq!(TcPacket::MAX_ENCODED_SIZE, 14 + 256);
+    }
+
+    #[test]
+    fn tc_packet_with_sequence_bumps_version() {
+        let tc_packet = TcPacket::new(DeviceId::System, Timestamp(0), payload(3u8));
+        assert_eq!(tc_packet.sequence(), None);
+
+        let tc_packet = tc_packet.with_sequence(0x1234);
+        assert_eq!(tc_packet.version(), ProtocolVersion::V2);
+        assert_eq!(tc_packet.sequence(), Some(0x1234));
+        assert_eq!(
+            tc_packet.size(),
+            TcPacket::OVERHEAD + TcPacket::SEQUENCE_SIZE + 1
+        );
     }
 
     #[test]
@@ -589,4 +1489,178 @@ mod tests {
         let packet = Packet::TmPacket(tm_packet);
         assert!(!packet.is_tc_packet());
     }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn timestamp_datetime_round_trip() {
+        let timestamp = Timestamp::new(1_234_567).unwrap();
+
+        let datetime = timestamp.to_datetime();
+
+        assert_eq!(datetime.timestamp(), 1);
+        assert_eq!(datetime.timestamp_subsec_micros(), 234_567);
+        assert_eq!(Timestamp::from_datetime(datetime).unwrap(), timestamp);
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn timestamp_from_datetime_rejects_out_of_range() {
+        let before_epoch = chrono::DateTime::from_timestamp_micros(-1).unwrap();
+        let too_late = chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+
+        assert!(matches!(
+            Timestamp::from_datetime(before_epoch),
+            Err(TimestampError::BeforeEpoch)
+        ));
+        assert!(matches!(
+            Timestamp::from_datetime(too_late),
+            Err(TimestampError::ValueTooLarge(_))
+        ));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn packet_json_round_trip_is_compact() {
+        let packet = Packet::TmPacket(TmPacket::new(
+            DeviceId::System,
+            Timestamp(0),
+            Payload::from_raw_bytes([0xDE, 0xAD]).unwrap(),
+        ));
+
+        let json = serde_json::to_string(&packet).unwrap();
+
+        assert!(json.contains(r#""payload":"dead""#));
+        assert_eq!(serde_json::from_str::<Packet>(&json).unwrap(), packet);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn packet_json_is_flat_and_tagged() {
+        let packet = Packet::TcPacket(
+            TcPacket::new(DeviceId::Gps, Timestamp(10), payload(7)).with_sequence(3),
+        );
+
+        let json = serde_json::to_value(packet).unwrap();
+
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "kind": "tc",
+                "version": "V2",
+                "device_id": "Gps",
+                "sequence": 3,
+                "timestamp": 10,
+                "payload": "07",
+            })
+        );
+        assert_eq!(serde_json::from_value::<Packet>(json).unwrap(), packet);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn packet_json_rejects_inconsistent_fields() {
+        let missing_sequence = r#"{"kind":"tm","version":"V2","device_id":"Gps","sequence":null,"timestamp":10,"payload":""}"#;
+        let large_timestamp = r#"{"kind":"tm","version":"V1","device_id":"Gps","sequence":null,"timestamp":1099511627776,"payload":""}"#;
+        let unknown_kind = r#"{"kind":"xx","version":"V1","device_id":"Gps","sequence":null,"timestamp":10,"payload":""}"#;
+        let supported_as_newer = r#"{"kind":"tm","version":{"Newer":2},"device_id":"Gps","sequence":null,"timestamp":10,"payload":""}"#;
+        let zero_as_newer = r#"{"kind":"tm","version":{"Newer":0},"device_id":"Gps","sequence":null,"timestamp":10,"payload":""}"#;
+        let newer = r#"{"kind":"tm","version":{"Newer":3},"device_id":"Gps","sequence":null,"timestamp":10,"payload":""}"#;
+
+        assert!(serde_json::from_str::<Packet>(missing_sequence).is_err());
+        assert!(serde_json::from_str::<Packet>(large_timestamp).is_err());
+        assert!(serde_json::from_str::<Packet>(unknown_kind).is_err());
+        assert!(serde_json::from_str::<Packet>(supported_as_newer).is_err());
+        assert!(serde_json::from_str::<Packet>(zero_as_newer).is_err());
+        let Packet::TmPacket(packet) = serde_json::from_str::<Packet>(newer).unwrap() else {
+            unreachable!()
+        };
+        assert_eq!(packet.version(), ProtocolVersion::Newer(3));
+    }
+
+    #[test]
+    fn packet_content_id_is_stable() {
+        let packet = Packet::TmPacket(TmPacket::new(
+            DeviceId::Gps,
+            Timestamp(10),
+            Payload::from_raw_bytes([0xEF, 0xCD, 0xAB]).unwrap(),
+        ));
+
+        assert_eq!(packet.content_id(), 0xec59c988769dbb5a);
+    }
+
+    #[test]
+    fn packet_content_id_depends_on_control_flags() {
+        let packet = TmPacket::new(
+            DeviceId::Gps,
+            Timestamp(10),
+            Payload::from_raw_bytes([0xEF, 0xCD, 0xAB]).unwrap(),
+        );
+        let mut flagged = packet;
+        flagged.0.control_flags = 0b01;
+        let mut other_flagged = packet;
+        other_flagged.0.control_flags = 0b10;
+
+        let id = Packet::TmPacket(packet).content_id();
+        let flagged_id = Packet::TmPacket(flagged).content_id();
+
+        assert_eq!(id, 0xec59c988769dbb5a);
+        assert_ne!(flagged_id, id);
+        assert_ne!(Packet::TmPacket(other_flagged).content_id(), flagged_id);
+    }
+
+    #[test]
+    fn packet_content_id_depends_on_contents() {
+        let packet = TmPacket::new(DeviceId::Gps, Timestamp(10), payload(1));
+        let id = Packet::TmPacket(packet).content_id();
+
+        assert_ne!(
+            Packet::TmPacket(TmPacket::new(DeviceId::Gps, Timestamp(10), payload(2))).content_id(),
+            id
+        );
+        assert_ne!(
+            Packet::TmPacket(TmPacket::new(DeviceId::Gps, Timestamp(11), payload(1))).content_id(),
+            id
+        );
+        assert_ne!(
+            Packet::TmPacket(TmPacket::new(DeviceId::Camera, Timestamp(10), payload(1)))
+                .content_id(),
+            id
+        );
+        assert_ne!(Packet::TmPacket(packet.with_sequence(0)).content_id(), id);
+    }
+
+    #[test]
+    fn packet_kind_conversions_preserve_fields() {
+        let tc = TcPacket::new(DeviceId::Camera, Timestamp(42), payload(9)).with_sequence(3);
+
+        let Packet::TmPacket(tm) = Packet::TcPacket(tc).into_tm() else {
+            panic!("Converted packet is not TmPacket")
+        };
+
+        assert_eq!(tm.version(), ProtocolVersion::V2);
+        assert_eq!(tm.device_id(), &DeviceId::Camera);
+        assert_eq!(tm.sequence(), Some(3));
+        assert_eq!(tm.timestamp(), &Timestamp(42));
+        assert_eq!(*tm.payload(), payload(9));
+        assert_eq!(Packet::TmPacket(tm).into_tc(), Packet::TcPacket(tc));
+        assert_eq!(Packet::TcPacket(tc).into_tc(), Packet::TcPacket(tc));
+    }
+
+    #[test]
+    fn packet_set_payload_replaces_payload_of_either_kind() {
+        let mut tm_packet =
+            Packet::TmPacket(TmPacket::new(DeviceId::System, Timestamp(0), payload(1)));
+        let mut tc_packet =
+            Packet::TcPacket(TcPacket::new(DeviceId::System, Timestamp(0), payload(1)));
+
+        tm_packet.set_payload(payload(2));
+        *tc_packet.payload_mut() = payload(3);
+
+        let (Packet::TmPacket(tm_packet), Packet::TcPacket(tc_packet)) = (tm_packet, tc_packet)
+        else {
+            panic!("Packet kind changed")
+        };
+        assert_eq!(*tm_packet.payload(), payload(2));
+        assert_eq!(*tc_packet.payload(), payload(3));
+    }
 }
@@ -1,5 +1,5 @@
 #[cfg(feature = "serde")]
-use serde::{Deserialize, Serialize};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 
 /// The error type for operations interacting with [`Payload`]
 #[derive(thiserror::Error, Debug)]
@@ -11,10 +11,26 @@ pub enum PayloadError {
     PayloadTooLong(usize),
 }
 
+/// Byte order of data exchanged with code outside the protocol
+///
+/// Payloads are always little endian on the wire. This only describes the order of the bytes
+/// passed to or returned from accessors such as [`Payload::from_u16_bytes`] and
+/// [`Payload::to_u16_bytes`], which convert as needed.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Endian {
+    /// Least significant byte first, as used by the protocol
+    #[default]
+    Little,
+    /// Most significant byte first
+    Big,
+}
+
 /// The contents of a packet.
 ///
 /// Internally, the payload is stored as a little endian byte sequence, since that's the format
-/// used by the protocol.
+/// used by the protocol. Data from a source with a different byte order can be converted with the
+/// accessors taking an [`Endian`].
 ///
 /// # Example
 /// ```
@@ -23,11 +39,20 @@ pub enum PayloadError {
 /// assert_eq!(payload.as_bytes(), [0xFF, 0x00]);
 /// # Ok::<(), Box<dyn std::error::Error>>(())
 /// ```
-#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+///
+/// # Serialization
+/// With the `serde` feature, only the bytes in use are serialized. Human readable formats, such as
+/// JSON, represent them as a lowercase hex string (e.g. `"ff00"`), while other formats use a byte
+/// array. Deserialization validates the length, so an inconsistent payload can't be constructed.
+///
+/// # Unused bytes
+/// The bytes of the backing array past the length of the payload are always zero, so stale data,
+/// such as the contents of a previous telecommand, can't leak from a reused payload. Every
+/// operation shrinking a payload, such as [`Payload::clear`] and [`Payload::truncate`], zeroes the
+/// bytes it drops.
+#[derive(Copy, Clone, Debug)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Payload {
-    #[cfg_attr(feature = "serde", serde(with = "serde_with::As::<serde_with::Bytes>"))]
     data: [u8; 255],
     length: usize,
 }
@@ -93,6 +118,65 @@ impl Payload {
         Ok(payload)
     }
 
+    /// Construct a new payload from the given bytes, keeping only the first [`Payload::MAX_SIZE`]
+    /// bytes if there are more.
+    ///
+    /// This is meant for best-effort data, such as logs, where losing the end of a record is
+    /// preferable to losing it entirely. Prefer [`Payload::from_raw_bytes`] otherwise, which
+    /// fails instead of dropping data. The returned flag is `true` if the bytes were truncated.
+    ///
+    /// # Examples
+    /// ```
+    /// # use orbipacket::Payload;
+    /// let (payload, truncated) = Payload::from_bytes_truncating(&[0xAB; 300]);
+    /// assert_eq!(payload.as_bytes(), [0xAB; Payload::MAX_SIZE]);
+    /// assert!(truncated);
+    ///
+    /// let (payload, truncated) = Payload::from_bytes_truncating(b"hello");
+    /// assert_eq!(payload.as_bytes(), b"hello");
+    /// assert!(!truncated);
+    /// ```
+    pub fn from_bytes_truncating(bytes: &[u8]) -> (Self, bool) {
+        let truncated = bytes.len() > Self::MAX_SIZE;
+        let bytes = &bytes[..bytes.len().min(Self::MAX_SIZE)];
+        let mut payload = Self::new();
+        payload.data[..bytes.len()].copy_from_slice(bytes);
+        payload.length = bytes.len();
+        (payload, truncated)
+    }
+
+    /// Create a payload from the bytes yielded by an iterator.
+    ///
+    /// Unlike [`Payload::from_raw_bytes`], this doesn't require the bytes to be gathered in a
+    /// slice first. As with it, the bytes are expected in little endian.
+    ///
+    /// # Errors
+    /// If the iterator yields more than [`Payload::MAX_SIZE`] bytes, an error variant is returned.
+    /// The iterator is only consumed up to the first excess byte, so the length reported by the
+    /// error is `Payload::MAX_SIZE + 1`.
+    ///
+    /// # Example
+    /// ```
+    /// # use orbipacket::Payload;
+    /// let samples = [1u16, 2, 3];
+    /// let payload = Payload::from_iter_bytes(samples.iter().flat_map(|s| s.to_le_bytes()))?;
+    /// assert_eq!(payload.as_bytes(), [1, 0, 2, 0, 3, 0]);
+    ///
+    /// assert!(Payload::from_iter_bytes(core::iter::repeat(0)).is_err());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn from_iter_bytes<I: IntoIterator<Item = u8>>(iter: I) -> Result<Self, PayloadError> {
+        let mut payload = Self::new();
+        for byte in iter {
+            if payload.length == Self::MAX_SIZE {
+                return Err(PayloadError::PayloadTooLong(Self::MAX_SIZE + 1));
+            }
+            payload.data[payload.length] = byte;
+            payload.length += 1;
+        }
+        Ok(payload)
+    }
+
     pub fn from_u8(value: u8) -> Self {
         // A u8 is guaranteed to fit inside a payload
         Self::from_raw_bytes(value.to_le_bytes()).unwrap()
@@ -178,6 +262,214 @@ impl Payload {
     pub fn length(&self) -> usize {
         self.length
     }
+
+    /// Empty the payload, zeroing its contents.
+    ///
+    /// # Example
+    /// ```
+    /// # use orbipacket::Payload;
+    /// let mut payload = Payload::from_raw_bytes(b"secret")?;
+    /// payload.clear();
+    /// assert_eq!(payload, Payload::new());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    /// Shorten the payload to `len` bytes, zeroing the dropped bytes.
+    ///
+    /// Nothing happens if the payload is already at most `len` bytes long.
+    ///
+    /// # Example
+    /// ```
+    /// # use orbipacket::Payload;
+    /// let mut payload = Payload::from_raw_bytes([1, 2, 3])?;
+    /// payload.truncate(1);
+    /// assert_eq!(payload.as_bytes(), [1]);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.length {
+            self.data[len..self.length].fill(0);
+            self.length = len;
+        }
+    }
+
+    /// Check if the payload starts with the given bytes.
+    ///
+    /// This is useful to dispatch on an opcode stored at the start of the payload.
+    ///
+    /// # Example
+    /// ```
+    /// # use orbipacket::Payload;
+    /// let payload = Payload::from_raw_bytes([0x01, 0xAB, 0xCD])?;
+    /// assert!(payload.starts_with(&[0x01]));
+    /// assert!(!payload.starts_with(&[0xAB]));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn starts_with(&self, prefix: &[u8]) -> bool {
+        self.as_bytes().starts_with(prefix)
+    }
+
+    /// Returns the first byte of the payload and the remaining bytes, or `None` if it's empty.
+    ///
+    /// This is useful when the payload starts with an opcode, followed by its arguments.
+    ///
+    /// # Example
+    /// ```
+    /// # use orbipacket::Payload;
+    /// let payload = Payload::from_raw_bytes([0x01, 0xAB, 0xCD])?;
+    /// assert_eq!(payload.split_first(), Some((0x01, &[0xAB, 0xCD][..])));
+    /// assert_eq!(Payload::new().split_first(), None);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn split_first(&self) -> Option<(u8, &[u8])> {
+        self.as_bytes()
+            .split_first()
+            .map(|(first, rest)| (*first, rest))
+    }
+
+    /// Returns a byte or range of bytes of the payload, or `None` if out of bounds.
+    ///
+    /// Unlike indexing [`Payload::as_bytes`], this never panics.
+    ///
+    /// # Example
+    /// ```
+    /// # use orbipacket::Payload;
+    /// let payload = Payload::from_raw_bytes([0x01, 0xAB, 0xCD])?;
+    /// assert_eq!(payload.get(1..), Some(&[0xAB, 0xCD][..]));
+    /// assert_eq!(payload.get(0), Some(&0x01));
+    /// assert_eq!(payload.get(2..4), None);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn get<I: core::slice::SliceIndex<[u8]>>(&self, index: I) -> Option<&I::Output> {
+        self.as_bytes().get(index)
+    }
+
+    /// Returns the first byte of the payload, or `None` if it's empty.
+    pub fn first(&self) -> Option<u8> {
+        self.as_bytes().first().copied()
+    }
+
+    /// Returns the last byte of the payload, or `None` if it's empty.
+    pub fn last(&self) -> Option<u8> {
+        self.as_bytes().last().copied()
+    }
+}
+
+/// Generate accessors converting between a single scalar payload and bytes in a given order.
+macro_rules! endian_accessors {
+    ($($from:ident, $to:ident => $ty:ty),* $(,)?) => {
+        /// # Byte order conversions
+        ///
+        /// The payload is always stored, and sent, in little endian. These accessors only convert
+        /// the bytes exchanged with the caller, so that data from a big endian source can be
+        /// handled without swapping bytes by hand.
+        ///
+        /// # Example
+        /// ```
+        /// # use orbipacket::{payload::Endian, Payload};
+        /// let payload = Payload::from_u16_bytes([0x12, 0x34], Endian::Big);
+        /// assert_eq!(payload.as_bytes(), [0x34, 0x12]);
+        /// assert_eq!(payload, Payload::from_u16(0x1234));
+        /// assert_eq!(payload.to_u16_bytes(Endian::Big), Some([0x12, 0x34]));
+        /// ```
+        impl Payload {
+            $(
+                #[doc = concat!(
+                    "Create a payload holding the `", stringify!($ty),
+                    "` represented by the given bytes, in the given order."
+                )]
+                pub fn $from(bytes: [u8; core::mem::size_of::<$ty>()], endian: Endian) -> Self {
+                    let value = match endian {
+                        Endian::Little => <$ty>::from_le_bytes(bytes),
+                        Endian::Big => <$ty>::from_be_bytes(bytes),
+                    };
+                    // Unwrapping is safe here because a scalar is guaranteed to fit inside a
+                    // payload
+                    Self::from_raw_bytes(value.to_le_bytes()).unwrap()
+                }
+
+                #[doc = concat!(
+                    "Returns the bytes of the `", stringify!($ty),
+                    "` held by the payload, in the given order, or `None` if the payload doesn't ",
+                    "have the size of a `", stringify!($ty), "`."
+                )]
+                pub fn $to(&self, endian: Endian) -> Option<[u8; core::mem::size_of::<$ty>()]> {
+                    let value = <$ty>::from_le_bytes(self.as_bytes().try_into().ok()?);
+                    Some(match endian {
+                        Endian::Little => value.to_le_bytes(),
+                        Endian::Big => value.to_be_bytes(),
+                    })
+                }
+            )*
+        }
+    };
+}
+
+endian_accessors!(
+    from_u16_bytes, to_u16_bytes => u16,
+    from_i16_bytes, to_i16_bytes => i16,
+    from_u32_bytes, to_u32_bytes => u32,
+    from_i32_bytes, to_i32_bytes => i32,
+    from_u64_bytes, to_u64_bytes => u64,
+    from_i64_bytes, to_i64_bytes => i64,
+    from_u128_bytes, to_u128_bytes => u128,
+    from_i128_bytes, to_i128_bytes => i128,
+    from_f32_bytes, to_f32_bytes => f32,
+    from_f64_bytes, to_f64_bytes => f64,
+);
+
+// Comparisons and hashing only consider the bytes in use, ignoring whatever is left in the rest
+// of the backing array, so that they match what goes on the wire
+
+impl PartialEq for Payload {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_bytes() == other.as_bytes()
+    }
+}
+
+impl Eq for Payload {}
+
+/// Compares the bytes in use with a slice.
+///
+/// # Example
+/// ```
+/// # use orbipacket::Payload;
+/// let payload = Payload::from_raw_bytes([0xAB, 0xCD])?;
+/// assert_eq!(payload, [0xAB, 0xCD][..]);
+/// assert_eq!(payload, &[0xAB, 0xCD][..]);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+impl PartialEq<[u8]> for Payload {
+    fn eq(&self, other: &[u8]) -> bool {
+        self.as_bytes() == other
+    }
+}
+
+impl PartialEq<&[u8]> for Payload {
+    fn eq(&self, other: &&[u8]) -> bool {
+        self.as_bytes() == *other
+    }
+}
+
+impl PartialOrd for Payload {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Payload {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.as_bytes().cmp(other.as_bytes())
+    }
+}
+
+impl core::hash::Hash for Payload {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.as_bytes().hash(state);
+    }
 }
 
 impl Default for Payload {
@@ -186,6 +478,27 @@ impl Default for Payload {
     }
 }
 
+/// Collects bytes into a payload, as with [`Payload::from_iter_bytes`].
+///
+/// # Panics
+/// Panics if the iterator yields more than [`Payload::MAX_SIZE`] bytes. Use
+/// [`Payload::from_iter_bytes`] to handle this case instead.
+///
+/// # Example
+/// ```
+/// # use orbipacket::Payload;
+/// let payload: Payload = [1u16, 2].iter().flat_map(|s| s.to_le_bytes()).collect();
+/// assert_eq!(payload.as_bytes(), [1, 0, 2, 0]);
+/// ```
+impl FromIterator<u8> for Payload {
+    fn from_iter<I: IntoIterator<Item = u8>>(iter: I) -> Self {
+        match Self::from_iter_bytes(iter) {
+            Ok(payload) => payload,
+            Err(error) => panic!("{error}"),
+        }
+    }
+}
+
 impl TryFrom<&[u8]> for Payload {
     type Error = PayloadError;
 
@@ -194,8 +507,327 @@ impl TryFrom<&[u8]> for Payload {
     }
 }
 
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Payload {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let length = u.int_in_range(0..=Self::MAX_SIZE)?;
+        let bytes = u.bytes(length.min(u.len()))?;
+        // Unwrapping is safe here because the length is at most MAX_SIZE
+        Ok(Self::from_raw_bytes(bytes).unwrap())
+    }
+}
+
 impl AsRef<[u8]> for Payload {
     fn as_ref(&self) -> &[u8] {
         self.as_bytes()
     }
 }
+
+/// Gives access to the slice methods over the bytes in use, as returned by
+/// [`Payload::as_bytes`].
+///
+/// Note that the inherent [`Payload::first`], [`Payload::last`], [`Payload::get`],
+/// [`Payload::split_first`] and [`Payload::starts_with`] methods take precedence over the slice
+/// ones.
+///
+/// # Example
+/// ```
+/// # use orbipacket::Payload;
+/// let payload = Payload::from_raw_bytes([0x01, 0xAB, 0xCD])?;
+/// assert_eq!(payload.len(), 3);
+/// assert_eq!(payload[1..], [0xAB, 0xCD]);
+/// assert_eq!(payload.iter().map(|&b| b as u32).sum::<u32>(), 0x01 + 0xAB + 0xCD);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+impl core::ops::Deref for Payload {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+#[cfg(feature = "serde")]
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+#[cfg(feature = "serde")]
+impl Serialize for Payload {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            let mut hex = [0u8; 2 * Self::MAX_SIZE];
+            for (digits, byte) in hex.chunks_exact_mut(2).zip(self.as_bytes()) {
+                digits[0] = HEX_DIGITS[(byte >> 4) as usize];
+                digits[1] = HEX_DIGITS[(byte & 0x0F) as usize];
+            }
+            // Unwrapping is safe here because hex digits are valid UTF-8
+            serializer.serialize_str(core::str::from_utf8(&hex[..2 * self.length]).unwrap())
+        } else {
+            serializer.serialize_bytes(self.as_bytes())
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Payload {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(PayloadVisitor)
+        } else {
+            deserializer.deserialize_bytes(PayloadVisitor)
+        }
+    }
+}
+
+/// Builds a [`Payload`] from a hex string, bytes or a sequence of bytes
+#[cfg(feature = "serde")]
+struct PayloadVisitor;
+
+#[cfg(feature = "serde")]
+impl<'de> de::Visitor<'de> for PayloadVisitor {
+    type Value = Payload;
+
+    fn expecting(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(
+            f,
+            "a hex string or bytes, at most {} bytes long",
+            Payload::MAX_SIZE
+        )
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        let hex = v.as_bytes();
+        if hex.len() % 2 != 0 || hex.len() > 2 * Payload::MAX_SIZE {
+            return Err(E::invalid_length(hex.len() / 2, &self));
+        }
+
+        let digit = |c: u8| match c {
+            b'0'..=b'9' => Ok(c - b'0'),
+            b'a'..=b'f' => Ok(c - b'a' + 10),
+            b'A'..=b'F' => Ok(c - b'A' + 10),
+            _ => Err(E::invalid_value(de::Unexpected::Str(v), &self)),
+        };
+
+        let mut bytes = [0u8; Payload::MAX_SIZE];
+        for (byte, digits) in bytes.iter_mut().zip(hex.chunks_exact(2)) {
+            *byte = digit(digits[0])? << 4 | digit(digits[1])?;
+        }
+        Payload::from_raw_bytes(&bytes[..hex.len() / 2]).map_err(E::custom)
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Payload::from_raw_bytes(v).map_err(|_| E::invalid_length(v.len(), &self))
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bytes = [0u8; Payload::MAX_SIZE];
+        let mut length = 0;
+        while let Some(byte) = seq.next_element()? {
+            if length == Payload::MAX_SIZE {
+                return Err(de::Error::invalid_length(length + 1, &self));
+            }
+            bytes[length] = byte;
+            length += 1;
+        }
+        Payload::from_raw_bytes(&bytes[..length]).map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn endian_accessors_keep_payload_little_endian() {
+        let value = 0x0102_0304u32;
+        let from_be = Payload::from_u32_bytes(value.to_be_bytes(), Endian::Big);
+        let from_le = Payload::from_u32_bytes(value.to_le_bytes(), Endian::Little);
+        assert_eq!(from_be, Payload::from_u32(value));
+        assert_eq!(from_le, Payload::from_u32(value));
+
+        assert_eq!(from_be.to_u32_bytes(Endian::Big), Some(value.to_be_bytes()));
+        assert_eq!(
+            from_be.to_u32_bytes(Endian::Little),
+            Some(value.to_le_bytes())
+        );
+        assert_eq!(
+            Payload::from_f64_bytes(1.5f64.to_be_bytes(), Endian::Big).to_f64_bytes(Endian::Big),
+            Some(1.5f64.to_be_bytes())
+        );
+        assert_eq!(from_be.to_u16_bytes(Endian::Big), None);
+    }
+
+    /// Build a payload with the given bytes, leaving `garbage` in the unused part of the array
+    fn payload_with_garbage(bytes: &[u8], garbage: u8) -> Payload {
+        let mut payload = Payload {
+            data: [garbage; Payload::MAX_SIZE],
+            length: bytes.len(),
+        };
+        payload.data[..bytes.len()].copy_from_slice(bytes);
+        payload
+    }
+
+    fn hash(payload: &Payload) -> u64 {
+        use core::hash::{Hash, Hasher};
+
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        payload.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn shrinking_payload_zeroes_unused_bytes() {
+        let mut payload = Payload::from_raw_bytes([0xAA; 10]).unwrap();
+        payload.truncate(4);
+        assert_eq!(payload.as_bytes(), [0xAA; 4]);
+        assert!(payload.data[4..].iter().all(|&b| b == 0));
+
+        payload.clear();
+        assert_eq!(payload.length(), 0);
+        assert!(payload.data.iter().all(|&b| b == 0));
+
+        // Rebuilding a reused payload with shorter contents doesn't keep the old tail
+        let mut packet = crate::TcPacket::new(
+            crate::DeviceId::System,
+            crate::Timestamp::new(0).unwrap(),
+            Payload::from_raw_bytes([0xAA; 10]).unwrap(),
+        );
+        *packet.payload_mut() = Payload::from_raw_bytes([0xBB; 3]).unwrap();
+        assert_eq!(packet.payload().as_bytes(), [0xBB; 3]);
+        assert!(packet.payload().data[3..].iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn from_iter_bytes_accepts_max_size() {
+        let payload = Payload::from_iter_bytes((0..=254).map(|i| i as u8)).unwrap();
+
+        assert_eq!(payload.length(), Payload::MAX_SIZE);
+        assert_eq!(payload.as_bytes()[254], 254);
+    }
+
+    #[test]
+    fn from_iter_bytes_rejects_too_long() {
+        let result = Payload::from_iter_bytes([0u8; Payload::MAX_SIZE + 10]);
+
+        assert!(matches!(
+            result,
+            Err(PayloadError::PayloadTooLong(len)) if len == Payload::MAX_SIZE + 1
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "payload too long")]
+    fn collect_panics_when_too_long() {
+        let _: Payload = core::iter::repeat(1u8)
+            .take(Payload::MAX_SIZE + 1)
+            .collect();
+    }
+
+    #[test]
+    fn slice_comparisons_ignore_unused_bytes() {
+        let payload = payload_with_garbage(&[1, 2], 0xFF);
+
+        assert_eq!(payload, [1, 2][..]);
+        assert_eq!(payload, &[1, 2][..]);
+        assert_ne!(payload, [1, 2, 0xFF][..]);
+        assert!(payload.starts_with(&[1]));
+        assert!(!payload.starts_with(&[1, 2, 0xFF]));
+    }
+
+    #[test]
+    fn accessors_ignore_unused_bytes() {
+        let payload = payload_with_garbage(&[1, 2], 0xFF);
+
+        assert_eq!(payload.first(), Some(1));
+        assert_eq!(payload.last(), Some(2));
+        assert_eq!(payload.get(2), None);
+        assert_eq!(payload.get(1..3), None);
+        assert_eq!(payload.split_first(), Some((1, &[2][..])));
+
+        let empty = payload_with_garbage(&[], 0xFF);
+        assert_eq!(empty.first(), None);
+        assert_eq!(empty.last(), None);
+        assert_eq!(empty.split_first(), None);
+    }
+
+    #[test]
+    fn deref_ignores_unused_bytes() {
+        let payload = payload_with_garbage(&[1, 2], 0xFF);
+
+        assert_eq!(payload.len(), 2);
+        assert_eq!(&*payload, [1, 2]);
+        assert!(!payload.contains(&0xFF));
+        assert_eq!(payload.chunks(1).count(), 2);
+    }
+
+    #[test]
+    fn comparisons_ignore_unused_bytes() {
+        let clean = Payload::from_raw_bytes([1, 2, 3]).unwrap();
+        let dirty = payload_with_garbage(&[1, 2, 3], 0xAA);
+
+        assert_eq!(clean, dirty);
+        assert_eq!(clean.cmp(&dirty), core::cmp::Ordering::Equal);
+        assert_eq!(hash(&clean), hash(&dirty));
+    }
+
+    #[test]
+    fn ordering_follows_active_bytes() {
+        let short = payload_with_garbage(&[1, 2], 0xFF);
+        let long = payload_with_garbage(&[1, 2, 0], 0x00);
+        let larger = payload_with_garbage(&[1, 3], 0x00);
+
+        assert!(short < long);
+        assert!(long < larger);
+        assert_ne!(short, long);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serialize_json_emits_only_active_bytes_as_hex() {
+        let payload = Payload::from_raw_bytes([0xAB, 0x01, 0xFF]).unwrap();
+
+        assert_eq!(serde_json::to_string(&payload).unwrap(), r#""ab01ff""#);
+        assert_eq!(serde_json::to_string(&Payload::new()).unwrap(), r#""""#);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize_json_round_trips() {
+        let payload = Payload::from_raw_bytes([0xAB, 0x01, 0xFF]).unwrap();
+
+        let json = serde_json::to_string(&payload).unwrap();
+
+        assert_eq!(serde_json::from_str::<Payload>(&json).unwrap(), payload);
+        assert_eq!(
+            serde_json::from_str::<Payload>(r#""AB01fF""#).unwrap(),
+            payload
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize_json_rejects_invalid_hex() {
+        assert!(serde_json::from_str::<Payload>(r#""abc""#).is_err());
+        assert!(serde_json::from_str::<Payload>(r#""zz""#).is_err());
+
+        let too_long = format!(r#""{}""#, "00".repeat(Payload::MAX_SIZE + 1));
+        assert!(serde_json::from_str::<Payload>(&too_long).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize_byte_sequence() {
+        let value = serde_json::json!([1, 2, 3]);
+
+        let payload = de::Deserializer::deserialize_seq(value, PayloadVisitor).unwrap();
+
+        assert_eq!(payload.as_bytes(), [1, 2, 3]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize_byte_sequence_rejects_too_long() {
+        let value = serde_json::to_value([0u8; Payload::MAX_SIZE + 1].as_slice()).unwrap();
+
+        assert!(de::Deserializer::deserialize_seq(value, PayloadVisitor).is_err());
+    }
+}
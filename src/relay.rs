@@ -0,0 +1,124 @@
+//! Forwarding of received frames, for store-and-forward nodes.
+
+use crate::decode::DecodeError;
+use crate::encode::EncodeError;
+use crate::Packet;
+
+/// Error that can occur when relaying a frame
+#[derive(thiserror::Error, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum RelayError {
+    /// The received frame doesn't hold a valid packet
+    #[error(transparent)]
+    Decode(#[from] DecodeError),
+    /// The packet couldn't be encoded for retransmission
+    #[error(transparent)]
+    Encode(#[from] EncodeError),
+}
+
+impl Packet {
+    /// Decode a frame and encode the packet it holds into another buffer, ready to be forwarded.
+    ///
+    /// The frame undergoes all the validations of [`Packet::decode_single`], including the CRC
+    /// check, so corrupt frames are never forwarded. Since `src` is unstuffed in-place, it is
+    /// mutated. `dst` must be at least [`Packet::MAX_ENCODE_BUFFER_SIZE`] bytes long to relay any
+    /// packet.
+    ///
+    /// # Example
+    /// ```
+    /// use orbipacket::{Packet, TmPacket, DeviceId, Timestamp, Payload};
+    ///
+    /// let packet = Packet::TmPacket(TmPacket::new(DeviceId::Gps, Timestamp::new(10)?, Payload::from_u8(7)));
+    /// let mut buffer = [0u8; Packet::MAX_ENCODE_BUFFER_SIZE];
+    /// let mut received = [0u8; TmPacket::MAX_ENCODED_SIZE];
+    /// let encoded = packet.encode(&mut buffer)?;
+    /// let len = encoded.len();
+    /// received[..len].copy_from_slice(encoded);
+    ///
+    /// let mut dst = [0u8; Packet::MAX_ENCODE_BUFFER_SIZE];
+    /// let forwarded = Packet::reencode(&mut received[..len], &mut dst)?;
+    ///
+    /// assert_eq!(forwarded, encoded);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn reencode<'a>(src: &mut [u8], dst: &'a mut [u8]) -> Result<&'a [u8], RelayError> {
+        let packet = Self::decode_single(src)?;
+        Ok(packet.encode(dst)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DeviceId, Payload, TcPacket, Timestamp, TmPacket};
+
+    #[test]
+    fn clean_frame_reencodes_identically() {
+        let packets = [
+            Packet::TmPacket(TmPacket::new(
+                DeviceId::Gps,
+                Timestamp::new(10).unwrap(),
+                Payload::from_raw_bytes([0, 1, 0, 2]).unwrap(),
+            )),
+            Packet::TcPacket(
+                TcPacket::new(DeviceId::System, Timestamp::new(0).unwrap(), Payload::new())
+                    .with_sequence(7),
+            ),
+        ];
+
+        for packet in packets {
+            let mut buffer = [0u8; Packet::MAX_ENCODE_BUFFER_SIZE];
+            let encoded = packet.encode(&mut buffer).unwrap();
+            let mut src = [0u8; TmPacket::MAX_ENCODED_SIZE];
+            src[..encoded.len()].copy_from_slice(encoded);
+            let mut dst = [0u8; Packet::MAX_ENCODE_BUFFER_SIZE];
+
+            let forwarded = Packet::reencode(&mut src[..encoded.len()], &mut dst).unwrap();
+
+            assert_eq!(forwarded, encoded);
+        }
+    }
+
+    #[test]
+    fn corrupt_frame_is_not_forwarded() {
+        let packet = Packet::TmPacket(TmPacket::new(
+            DeviceId::Gps,
+            Timestamp::new(10).unwrap(),
+            Payload::from_u8(1),
+        ));
+        let mut buffer = [0u8; Packet::MAX_ENCODE_BUFFER_SIZE];
+        let encoded = packet.encode(&mut buffer).unwrap();
+        let mut src = [0u8; TmPacket::MAX_ENCODED_SIZE];
+        src[..encoded.len()].copy_from_slice(encoded);
+        src[4] ^= 0x01;
+        let mut dst = [0u8; Packet::MAX_ENCODE_BUFFER_SIZE];
+
+        let result = Packet::reencode(&mut src[..encoded.len()], &mut dst);
+
+        assert!(matches!(
+            result,
+            Err(RelayError::Decode(DecodeError::InvalidChecksum { .. }))
+        ));
+    }
+
+    #[test]
+    fn small_destination_is_reported() {
+        let packet = Packet::TmPacket(TmPacket::new(
+            DeviceId::Gps,
+            Timestamp::new(10).unwrap(),
+            Payload::from_u8(1),
+        ));
+        let mut buffer = [0u8; Packet::MAX_ENCODE_BUFFER_SIZE];
+        let encoded = packet.encode(&mut buffer).unwrap();
+        let mut src = [0u8; TmPacket::MAX_ENCODED_SIZE];
+        src[..encoded.len()].copy_from_slice(encoded);
+        let mut dst = [0u8; 8];
+
+        let result = Packet::reencode(&mut src[..encoded.len()], &mut dst);
+
+        assert!(matches!(
+            result,
+            Err(RelayError::Encode(EncodeError::BufferTooSmall { .. }))
+        ));
+    }
+}
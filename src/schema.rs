@@ -0,0 +1,216 @@
+//! Human readable descriptions of packets, based on the payload layout of each device.
+//!
+//! This module requires the `alloc` feature, since the registered schemas are stored on the
+//! heap. It's meant for host side tools, such as ground station logs and dashboards, which would
+//! otherwise need to match on the device ID of every packet to interpret its payload.
+
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use core::fmt::{self, Display, Formatter};
+
+use crate::{DeviceId, Packet, Payload};
+
+/// The layout of the payloads sent by a device
+///
+/// A schema decodes a payload into a typed value, whose [`Display`] implementation describes it.
+/// Any closure taking a [`Payload`] and returning a [`Result`] with displayable variants is a
+/// schema, so simple layouts don't need a dedicated type.
+pub trait PayloadSchema {
+    /// The value a payload is decoded into
+    type Value: Display;
+    /// The error returned for payloads which don't follow the schema
+    type Error: Display;
+
+    /// Decode a payload following the schema.
+    fn decode(&self, payload: &Payload) -> Result<Self::Value, Self::Error>;
+}
+
+impl<F, T, E> PayloadSchema for F
+where
+    F: Fn(&Payload) -> Result<T, E>,
+    T: Display,
+    E: Display,
+{
+    type Value = T;
+    type Error = E;
+
+    fn decode(&self, payload: &Payload) -> Result<T, E> {
+        self(payload)
+    }
+}
+
+/// Object safe counterpart of [`PayloadSchema`], so that schemas with different value types can
+/// be stored together
+trait DescribePayload {
+    fn describe(&self, payload: &Payload, f: &mut Formatter<'_>) -> fmt::Result;
+}
+
+impl<S: PayloadSchema> DescribePayload for S {
+    fn describe(&self, payload: &Payload, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.decode(payload) {
+            Ok(value) => write!(f, "{value}"),
+            Err(error) => write!(f, "invalid payload ({error})"),
+        }
+    }
+}
+
+/// The payload schema of each device
+///
+/// # Example
+/// ```
+/// use orbipacket::{gps::GpsFix, schema::Registry, DeviceId, Packet, Payload, Timestamp, TmPacket};
+///
+/// let mut registry = Registry::new();
+/// registry.register(DeviceId::Gps, |payload: &Payload| {
+///     GpsFix::from_payload(payload).map(|fix| format!("{:.1}, {:.1} with {} satellites", fix.lat, fix.lon, fix.sats))
+/// });
+///
+/// let fix = GpsFix { lat: 38.7, lon: -9.3, alt: 120.5, sats: 7 };
+/// let packet = Packet::TmPacket(TmPacket::new(DeviceId::Gps, Timestamp::new(10)?, fix.to_payload()));
+///
+/// assert_eq!(
+///     packet.describe(&registry).to_string(),
+///     "Telemetry packet from GPS Device (ID 2) with timestamp 10 us: 38.7, -9.3 with 7 satellites",
+/// );
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Default)]
+pub struct Registry {
+    schemas: BTreeMap<DeviceId, Box<dyn DescribePayload>>,
+}
+
+impl Registry {
+    /// Create a registry without any schemas.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the schema of the payloads sent by a device, replacing any previous one.
+    pub fn register<S: PayloadSchema + 'static>(&mut self, device_id: DeviceId, schema: S) {
+        self.schemas.insert(device_id, Box::new(schema));
+    }
+
+    /// Check if a schema is registered for a device.
+    pub fn contains(&self, device_id: DeviceId) -> bool {
+        self.schemas.contains_key(&device_id)
+    }
+}
+
+impl fmt::Debug for Registry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.schemas.keys()).finish()
+    }
+}
+
+/// Human readable description of a packet, returned by [`Packet::describe`]
+#[derive(Debug)]
+struct Description<'a> {
+    packet: &'a Packet,
+    registry: &'a Registry,
+}
+
+impl Display for Description<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let (device_id, payload) = match self.packet {
+            Packet::TmPacket(packet) => {
+                write!(f, "{packet}")?;
+                (packet.device_id(), packet.payload())
+            }
+            Packet::TcPacket(packet) => {
+                write!(f, "{packet}")?;
+                (packet.device_id(), packet.payload())
+            }
+        };
+        f.write_str(": ")?;
+
+        match self.registry.schemas.get(device_id) {
+            Some(schema) => schema.describe(payload, f),
+            // Without a schema, the best that can be done is to show the raw bytes
+            None => {
+                f.write_str("[")?;
+                for (i, byte) in payload.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(" ")?;
+                    }
+                    write!(f, "{byte:02x}")?;
+                }
+                f.write_str("]")
+            }
+        }
+    }
+}
+
+impl Packet {
+    /// Describe the packet, interpreting its payload with the schema registered for its device.
+    ///
+    /// Payloads of devices without a schema are shown as hex bytes, and payloads which don't
+    /// follow their schema are shown with the error returned by it. See [`Registry`] for an
+    /// example.
+    pub fn describe<'a>(&'a self, registry: &'a Registry) -> impl Display + 'a {
+        Description {
+            packet: self,
+            registry,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{gps::GpsFix, TcPacket, Timestamp, TmPacket};
+
+    fn registry() -> Registry {
+        let mut registry = Registry::new();
+        registry.register(DeviceId::Gps, |payload: &Payload| {
+            GpsFix::from_payload(payload).map(|fix| fix.sats)
+        });
+        registry
+    }
+
+    #[test]
+    fn describes_payload_with_registered_schema() {
+        let fix = GpsFix {
+            sats: 9,
+            ..GpsFix::default()
+        };
+        let packet = Packet::TmPacket(TmPacket::new(
+            DeviceId::Gps,
+            Timestamp::new(5).unwrap(),
+            fix.to_payload(),
+        ));
+
+        assert_eq!(
+            packet.describe(&registry()).to_string(),
+            "Telemetry packet from GPS Device (ID 2) with timestamp 5 us: 9"
+        );
+    }
+
+    #[test]
+    fn describes_payload_not_following_schema() {
+        let packet = Packet::TmPacket(TmPacket::new(
+            DeviceId::Gps,
+            Timestamp::new(5).unwrap(),
+            Payload::from_u8(1),
+        ));
+
+        assert_eq!(
+            packet.describe(&registry()).to_string(),
+            "Telemetry packet from GPS Device (ID 2) with timestamp 5 us: invalid payload (invalid gps payload length (expected 21, found 1))"
+        );
+    }
+
+    #[test]
+    fn describes_payload_without_schema_as_hex() {
+        let packet = Packet::TcPacket(TcPacket::new(
+            DeviceId::Camera,
+            Timestamp::new(5).unwrap(),
+            Payload::from_u16(0xAB01),
+        ));
+
+        assert!(!registry().contains(DeviceId::Camera));
+        assert!(packet
+            .describe(&registry())
+            .to_string()
+            .ends_with(": [01 ab]"));
+    }
+}
@@ -0,0 +1,125 @@
+/// Outcome of observing a sequence number with a [`SequenceTracker`]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum SequenceStatus {
+    /// This is the first sequence number observed by the tracker
+    First,
+    /// The sequence number directly follows the previous one
+    InOrder,
+    /// Some sequence numbers were skipped since the previous one. The number of skipped
+    /// packets is returned as the contents of this variant.
+    Gap(u16),
+    /// The sequence number was already seen, or precedes the previous one. This usually
+    /// indicates a duplicated or reordered packet.
+    Stale,
+}
+
+/// Tracks the sequence numbers of received packets to detect lost packets.
+///
+/// Sequence numbers are expected to increase by one for each packet, wrapping around after
+/// [`u16::MAX`]. A tracker should be used for each stream of packets, typically one per device.
+///
+/// # Example
+/// ```
+/// # use orbipacket::sequence::{SequenceTracker, SequenceStatus};
+/// let mut tracker = SequenceTracker::new();
+///
+/// assert_eq!(tracker.observe(u16::MAX), SequenceStatus::First);
+/// assert_eq!(tracker.observe(0), SequenceStatus::InOrder);
+/// assert_eq!(tracker.observe(3), SequenceStatus::Gap(2));
+/// assert_eq!(tracker.lost(), 2);
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SequenceTracker {
+    expected: Option<u16>,
+    lost: u64,
+}
+
+impl SequenceTracker {
+    /// Create a tracker which hasn't observed any sequence number.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a received sequence number, reporting whether any packets were lost before it.
+    ///
+    /// Stale sequence numbers don't affect the tracker's state.
+    pub fn observe(&mut self, sequence: u16) -> SequenceStatus {
+        let Some(expected) = self.expected else {
+            self.expected = Some(sequence.wrapping_add(1));
+            return SequenceStatus::First;
+        };
+
+        // Differences in the upper half of the range are taken to be from the past
+        let skipped = sequence.wrapping_sub(expected);
+        if skipped >= 1 << 15 {
+            return SequenceStatus::Stale;
+        }
+
+        self.expected = Some(sequence.wrapping_add(1));
+        if skipped == 0 {
+            SequenceStatus::InOrder
+        } else {
+            self.lost += skipped as u64;
+            SequenceStatus::Gap(skipped)
+        }
+    }
+
+    /// Total number of packets found to be missing since the tracker was created
+    pub fn lost(&self) -> u64 {
+        self.lost
+    }
+
+    /// Forget the last observed sequence number, e.g. after the transmitter restarts.
+    ///
+    /// The count of lost packets is kept.
+    pub fn reset(&mut self) {
+        self.expected = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn observe_in_order_reports_no_loss() {
+        let mut tracker = SequenceTracker::new();
+
+        assert_eq!(tracker.observe(10), SequenceStatus::First);
+        assert_eq!(tracker.observe(11), SequenceStatus::InOrder);
+        assert_eq!(tracker.observe(12), SequenceStatus::InOrder);
+        assert_eq!(tracker.lost(), 0);
+    }
+
+    #[test]
+    fn observe_repeated_or_old_is_stale() {
+        let mut tracker = SequenceTracker::new();
+        tracker.observe(10);
+
+        assert_eq!(tracker.observe(10), SequenceStatus::Stale);
+        assert_eq!(tracker.observe(5), SequenceStatus::Stale);
+        assert_eq!(tracker.observe(11), SequenceStatus::InOrder);
+    }
+
+    #[test]
+    fn observe_gap_across_wraparound() {
+        let mut tracker = SequenceTracker::new();
+        tracker.observe(u16::MAX - 1);
+
+        assert_eq!(tracker.observe(1), SequenceStatus::Gap(2));
+        assert_eq!(tracker.lost(), 2);
+    }
+
+    #[test]
+    fn reset_keeps_lost_count() {
+        let mut tracker = SequenceTracker::new();
+        tracker.observe(0);
+        tracker.observe(5);
+        tracker.reset();
+
+        assert_eq!(tracker.observe(0), SequenceStatus::First);
+        assert_eq!(tracker.lost(), 4);
+    }
+}
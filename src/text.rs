@@ -0,0 +1,96 @@
+//! Base64 framing of packets, for transports which only carry text.
+//!
+//! This module requires the `base64` feature, which enables the `alloc` feature, since encoded
+//! frames are returned as [`String`]s.
+
+use alloc::string::String;
+
+use base64::engine::{general_purpose::STANDARD, Engine};
+
+use crate::decode::DecodeError;
+use crate::{Packet, TmPacket};
+
+impl Packet {
+    /// Encode the packet into a base64 string, for transmission over text-only channels.
+    ///
+    /// The packet is encoded as with [`Packet::encode`], terminating zero included, and the
+    /// resulting frame is encoded with the standard, padded, base64 alphabet.
+    ///
+    /// # Example
+    /// ```
+    /// use orbipacket::{Packet, TmPacket, DeviceId, Timestamp, Payload};
+    ///
+    /// let packet = Packet::TmPacket(TmPacket::new(DeviceId::Gps, Timestamp::new(10)?, Payload::from_u8(7)));
+    ///
+    /// let text = packet.encode_base64();
+    ///
+    /// assert!(text.is_ascii());
+    /// assert_eq!(Packet::decode_base64(&text)?, packet);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn encode_base64(&self) -> String {
+        let mut buffer = [0u8; Packet::MAX_ENCODE_BUFFER_SIZE];
+        // Unwrapping is safe here because the buffer can hold any packet
+        let frame = self.encode(&mut buffer).unwrap();
+        STANDARD.encode(frame)
+    }
+
+    /// Decode a packet from a base64 string, as produced by [`Packet::encode_base64`].
+    ///
+    /// # Errors
+    /// An error variant is returned if the text isn't valid base64, if it holds a frame longer
+    /// than [`TmPacket::MAX_ENCODED_SIZE`], or if the frame doesn't hold a valid packet (see
+    /// [`Packet::decode_single`]).
+    pub fn decode_base64(text: &str) -> Result<Self, DecodeError> {
+        let mut frame = [0u8; TmPacket::MAX_ENCODED_SIZE];
+        let len = STANDARD
+            .decode_slice(text, &mut frame)
+            .map_err(|error| match error {
+                base64::DecodeSliceError::OutputSliceTooSmall => {
+                    DecodeError::FrameTooLong(text.len() / 4 * 3)
+                }
+                base64::DecodeSliceError::DecodeError(_) => DecodeError::InvalidBase64,
+            })?;
+
+        Self::decode_single(&mut frame[..len])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DeviceId, Payload, TcPacket, Timestamp};
+
+    #[test]
+    fn encode_base64_encodes_frame() {
+        let packet = Packet::TcPacket(TcPacket::new(
+            DeviceId::System,
+            Timestamp::new(0).unwrap(),
+            Payload::new(),
+        ));
+        let mut buffer = [0u8; Packet::MAX_ENCODE_BUFFER_SIZE];
+        let frame = packet.encode(&mut buffer).unwrap();
+
+        let text = packet.encode_base64();
+
+        assert_eq!(STANDARD.decode(&text).unwrap(), frame);
+    }
+
+    #[test]
+    fn decode_base64_rejects_invalid_text() {
+        assert!(matches!(
+            Packet::decode_base64("not base64!"),
+            Err(DecodeError::InvalidBase64)
+        ));
+    }
+
+    #[test]
+    fn decode_base64_rejects_too_long_text() {
+        let text = STANDARD.encode([1u8; TmPacket::MAX_ENCODED_SIZE + 1]);
+
+        assert!(matches!(
+            Packet::decode_base64(&text),
+            Err(DecodeError::FrameTooLong(_))
+        ));
+    }
+}
@@ -0,0 +1,80 @@
+use core::fmt::Display;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[derive(thiserror::Error, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ProtocolVersionError {
+    #[error("unsupported protocol version: {0}")]
+    UnsupportedVersion(u8),
+}
+
+/// A version of the protocol, which determines the layout of a packet's header
+///
+/// # Example
+/// ```
+/// # use orbipacket::ProtocolVersion;
+/// let version = ProtocolVersion::try_from(2)?;
+/// assert_eq!(version, ProtocolVersion::V2);
+/// assert_eq!(version.as_u8(), 2);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ProtocolVersion {
+    /// The first version of the protocol
+    V1,
+    /// Adds a sequence number to the header
+    V2,
+    /// A version newer than the ones supported by this crate
+    ///
+    /// This is never returned by [`ProtocolVersion::try_from`], and only appears in packets
+    /// decoded with
+    /// [`DecodeOptions::lenient_version`](crate::decode::DecodeOptions::lenient_version), which
+    /// assume the layout of [`ProtocolVersion::V1`] for such packets. The version number is
+    /// always greater than that of [`ProtocolVersion::LATEST`].
+    Newer(u8),
+}
+
+impl ProtocolVersion {
+    /// Newest version of the protocol supported by this crate
+    pub const LATEST: ProtocolVersion = ProtocolVersion::V2;
+
+    /// The version number, as written on the wire
+    pub const fn as_u8(&self) -> u8 {
+        match self {
+            ProtocolVersion::V1 => 1,
+            ProtocolVersion::V2 => 2,
+            ProtocolVersion::Newer(version) => *version,
+        }
+    }
+
+    /// Check if the version is consistent, i.e. that a [`ProtocolVersion::Newer`] version doesn't
+    /// reuse the number of a supported or invalid version.
+    #[cfg(feature = "serde")]
+    pub(crate) const fn is_consistent(&self) -> bool {
+        match self {
+            ProtocolVersion::V1 | ProtocolVersion::V2 => true,
+            ProtocolVersion::Newer(version) => *version > Self::LATEST.as_u8(),
+        }
+    }
+}
+
+impl Display for ProtocolVersion {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "v{}", self.as_u8())
+    }
+}
+
+impl TryFrom<u8> for ProtocolVersion {
+    type Error = ProtocolVersionError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(ProtocolVersion::V1),
+            2 => Ok(ProtocolVersion::V2),
+            _ => Err(ProtocolVersionError::UnsupportedVersion(value)),
+        }
+    }
+}